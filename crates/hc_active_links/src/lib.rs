@@ -0,0 +1,54 @@
+use hdi::prelude::*;
+use std::collections::HashSet;
+
+/// Undeleted links of a type created by the link author before the link, that match a predicate
+///
+/// Reads the author's chain up to the link, so the result is the same for every validator.
+pub fn get_active_links<L, F>(
+    action: &CreateLink,
+    link_type: L,
+    matches: F,
+) -> ExternResult<Vec<CreateLink>>
+where
+    ScopedLinkType: TryFrom<L, Error = WasmError>,
+    F: Fn(&CreateLink) -> bool,
+{
+    let scoped_link_type = ScopedLinkType::try_from(link_type)?;
+    let agent_activity = must_get_agent_activity(
+        action.author.clone(),
+        ChainFilter::new(action.prev_action.clone()),
+    )?;
+
+    let mut created: Vec<(ActionHash, CreateLink)> = vec![];
+    let mut deleted: HashSet<ActionHash> = HashSet::new();
+    for activity in agent_activity {
+        match activity.action.action() {
+            Action::CreateLink(create_link)
+                if create_link.zome_index == scoped_link_type.zome_index
+                    && create_link.link_type == scoped_link_type.zome_type
+                    && matches(create_link) =>
+            {
+                created.push((activity.action.as_hash().clone(), create_link.clone()));
+            }
+            Action::DeleteLink(delete_link) => {
+                deleted.insert(delete_link.link_add_address.clone());
+            }
+            _ => {}
+        }
+    }
+
+    Ok(created
+        .into_iter()
+        .filter(|(hash, _)| !deleted.contains(hash))
+        .map(|(_, create_link)| create_link)
+        .collect())
+}
+
+/// Whether the link author already has an undeleted link of a type that matches a predicate
+pub fn has_active_link<L, F>(action: &CreateLink, link_type: L, matches: F) -> ExternResult<bool>
+where
+    ScopedLinkType: TryFrom<L, Error = WasmError>,
+    F: Fn(&CreateLink) -> bool,
+{
+    Ok(!get_active_links(action, link_type, matches)?.is_empty())
+}
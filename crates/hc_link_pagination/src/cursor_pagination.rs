@@ -0,0 +1,117 @@
+use crate::{Direction, Hashed, Timestamped};
+use hdk::prelude::*;
+use std::cmp::{Ordering, Reverse};
+
+/// Position of an item in a list sorted by timestamp, with the item hash as a tiebreaker.
+///
+/// Clients should treat a cursor as opaque and only pass back the `next_cursor` of a previous `Page`.
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq, Eq)]
+pub struct Cursor {
+    pub timestamp: Timestamp,
+    pub hash: AnyLinkableHash,
+}
+
+impl Cursor {
+    pub fn from_item<T>(item: &T) -> Self
+    where
+        T: Hashed + Timestamped,
+    {
+        Cursor {
+            timestamp: item.timestamp(),
+            hash: item.hash(),
+        }
+    }
+
+    fn cmp_item<T>(&self, item: &T) -> Ordering
+    where
+        T: Hashed + Timestamped,
+    {
+        self.timestamp
+            .cmp(&item.timestamp())
+            .then_with(|| self.hash.get_raw_39().cmp(item.hash().get_raw_39()))
+    }
+}
+
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+pub struct CursorPagination {
+    pub after_cursor: Option<Cursor>,
+    pub direction: Option<Direction>,
+    pub limit: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<Cursor>,
+    pub has_more: bool,
+}
+
+impl<T> Page<T> {
+    pub fn with_items<U>(self, items: Vec<U>) -> Page<U> {
+        Page {
+            items,
+            next_cursor: self.next_cursor,
+            has_more: self.has_more,
+        }
+    }
+}
+
+pub fn paginate_by_cursor<T>(
+    mut items: Vec<T>,
+    page: Option<CursorPagination>,
+) -> ExternResult<Page<T>>
+where
+    T: Clone + Hashed + Timestamped,
+{
+    let CursorPagination {
+        after_cursor,
+        direction,
+        limit,
+    } = match page {
+        Some(p) => p,
+        None => {
+            // Default sort by timestamp descending, everything on one page
+            items.sort_by_key(|i| Reverse(sort_key(i)));
+            return Ok(Page {
+                next_cursor: items.last().map(Cursor::from_item),
+                items,
+                has_more: false,
+            });
+        }
+    };
+
+    // Sort by (timestamp, hash) so that items sharing a timestamp keep a stable order
+    let ascending = matches!(direction, Some(Direction::Ascending));
+    match ascending {
+        true => items.sort_by_key(sort_key),
+        false => items.sort_by_key(|i| Reverse(sort_key(i))),
+    }
+
+    // Resume strictly after the cursor position, whether or not the cursor item still exists
+    let remaining: Vec<T> = match after_cursor {
+        Some(cursor) => items
+            .into_iter()
+            .filter(|i| match ascending {
+                true => cursor.cmp_item(i) == Ordering::Less,
+                false => cursor.cmp_item(i) == Ordering::Greater,
+            })
+            .collect(),
+        None => items,
+    };
+
+    let has_more = remaining.len() > limit;
+    let page_items: Vec<T> = remaining.into_iter().take(limit).collect();
+
+    Ok(Page {
+        next_cursor: page_items.last().map(Cursor::from_item),
+        items: page_items,
+        has_more,
+    })
+}
+
+fn sort_key<T>(item: &T) -> (Timestamp, Vec<u8>)
+where
+    T: Hashed + Timestamped,
+{
+    (item.timestamp(), item.hash().get_raw_39().to_vec())
+}
@@ -6,3 +6,5 @@ pub mod traits;
 pub use traits::*;
 pub mod direction;
 pub use direction::*;
+pub mod cursor_pagination;
+pub use cursor_pagination::*;
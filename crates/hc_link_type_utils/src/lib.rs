@@ -0,0 +1,64 @@
+use hdk::prelude::*;
+
+/// A link type of an integrity zome, with the zome index it has in this DNA
+///
+/// Zome indexes follow the order of integrity zomes in the DNA manifest, so they are
+/// resolved by zome name at runtime rather than hard-coded.
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResolvedLinkType {
+    pub zome_index: ZomeIndex,
+    pub link_type: LinkType,
+}
+
+impl ResolvedLinkType {
+    pub fn filter(&self) -> LinkTypeFilter {
+        LinkTypeFilter::single_type(self.zome_index, self.link_type)
+    }
+
+    pub fn matches(&self, zome_index: ZomeIndex, link_type: LinkType) -> bool {
+        self.zome_index == zome_index && self.link_type == link_type
+    }
+}
+
+/// Index of the named integrity zome in this DNA
+pub fn get_zome_index(zome_name: &str) -> ExternResult<ZomeIndex> {
+    let position = dna_info()?
+        .zome_names
+        .iter()
+        .position(|name| name.0 == zome_name)
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!(
+            "No zome named {} in this DNA",
+            zome_name
+        ))))?;
+
+    u8::try_from(position)
+        .map(ZomeIndex)
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest(String::from("Too many zomes"))))
+}
+
+/// Resolve a `LinkTypes` variant of the named integrity zome
+pub fn resolve_link_type<T>(zome_name: &str, link_type: T) -> ExternResult<ResolvedLinkType>
+where
+    LocalZomeTypeId: From<T>,
+{
+    Ok(ResolvedLinkType {
+        zome_index: get_zome_index(zome_name)?,
+        link_type: LinkType(LocalZomeTypeId::from(link_type).0),
+    })
+}
+
+/// Combine resolved link types, possibly of different zomes, into a single filter
+pub fn link_types_filter(link_types: &[ResolvedLinkType]) -> LinkTypeFilter {
+    let mut types: Vec<(ZomeIndex, Vec<LinkType>)> = vec![];
+    for resolved in link_types {
+        match types
+            .iter_mut()
+            .find(|(zome_index, _)| *zome_index == resolved.zome_index)
+        {
+            Some((_, zome_link_types)) => zome_link_types.push(resolved.link_type),
+            None => types.push((resolved.zome_index, vec![resolved.link_type])),
+        }
+    }
+
+    LinkTypeFilter::Types(types)
+}
@@ -1,7 +1,11 @@
-use hc_link_pagination::Timestamped;
+use hc_call_utils::call_local_zome;
+use hc_link_pagination::{Page, Timestamped};
 use hdk::prelude::*;
 use std::collections::BTreeMap;
 
+pub mod tags;
+pub use tags::*;
+
 #[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq, Eq)]
 pub enum LinkTarget {
     Mention(AgentPubKey),
@@ -44,18 +48,56 @@ pub struct FeedMew {
     pub mew: Mew,
     pub action: Action,
     pub action_hash: ActionHash,
-    pub replies_count: usize,
-    pub quotes_count: usize,
-    pub licks_count: usize,
-    pub mewmews_count: usize,
+    pub replies_count: Option<usize>,
+    pub quotes_count: Option<usize>,
+    pub licks_count: Option<usize>,
+    pub mewmews_count: Option<usize>,
     pub deleted_timestamp: Option<Timestamp>,
+    pub edited_timestamp: Option<Timestamp>,
     pub author_profile: Option<Profile>,
-    pub is_pinned: bool,
-    pub is_licked: bool,
-    pub is_mewmewed: bool,
-    pub is_replied: bool,
-    pub is_quoted: bool,
+    pub is_pinned: Option<bool>,
+    pub is_licked: Option<bool>,
+    pub is_mewmewed: Option<bool>,
+    pub is_replied: Option<bool>,
+    pub is_quoted: Option<bool>,
     pub original_mew: Option<EmbedMew>,
+    /// Number of reactions to the mew by reaction, licks included
+    pub reactions: Option<BTreeMap<String, usize>>,
+    /// The calling agent's own reactions to the mew
+    pub my_reactions: Option<Vec<String>>,
+}
+
+/// The reaction of a like, as named by the likes zome
+pub const LICK_REACTION: &str = "lick";
+
+/// Reactions to a hash by reaction, and the calling agent's own reactions to it
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, Default)]
+pub struct ReactionSummary {
+    pub counts: BTreeMap<String, usize>,
+    pub my_reactions: Vec<String>,
+}
+
+/// Which parts of a FeedMew's context to look up, the fields left out are set to None
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+pub struct FeedMewOptions {
+    /// Replies, quotes, licks, mewmews and reactions counts
+    pub include_counts: bool,
+    /// Whether the calling agent has pinned, licked, mewmewed, replied to or quoted the mew, and their reactions
+    pub include_viewer_flags: bool,
+    pub include_author_profile: bool,
+    /// The mew replied to, quoted or mewmewed
+    pub include_embed: bool,
+}
+
+impl Default for FeedMewOptions {
+    fn default() -> Self {
+        FeedMewOptions {
+            include_counts: true,
+            include_viewer_flags: true,
+            include_author_profile: true,
+            include_embed: true,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
@@ -67,6 +109,15 @@ pub struct EmbedMew {
     pub deleted_timestamp: Option<Timestamp>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct YarnNode {
+    pub action_hash: ActionHash,
+    /// None when the mew has been deleted, leaving a placeholder so its replies keep their place
+    pub feed_mew: Option<FeedMew>,
+    pub deleted_timestamp: Option<Timestamp>,
+    pub replies: Page<YarnNode>,
+}
+
 #[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
 pub struct Notification {
     pub notification_type: NotificationType,
@@ -82,7 +133,26 @@ impl Timestamped for Notification {
     }
 }
 
+/// Notifications of the same type about the same mew, close together in time
 #[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+pub struct GroupedNotification {
+    pub notification_type: NotificationType,
+    /// Distinct agents, most recent first
+    pub agents: Vec<AgentPubKey>,
+    pub latest_agent_profile: Option<Profile>,
+    /// Number of notifications in the group, which may exceed the number of agents
+    pub count: usize,
+    pub latest_timestamp: Timestamp,
+    pub feed_mew: Option<FeedMew>,
+}
+
+impl Timestamped for GroupedNotification {
+    fn timestamp(&self) -> Timestamp {
+        self.latest_timestamp
+    }
+}
+
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum NotificationType {
     MyMewLicked,
     MyMewUnlicked,
@@ -93,4 +163,61 @@ pub enum NotificationType {
     MyAgentFollowed,
     MyAgentUnfollowed,
     FollowedYarnResponded,
+    MyAgentFollowRequested,
+    MyFollowRequestAccepted,
+}
+
+/// A notification to push to another agent about something I did
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+pub struct SendNotificationSignalInput {
+    pub notification_type: NotificationType,
+    /// The agent to notify, or the author of the feed mew when None
+    pub recipient: Option<AgentPubKey>,
+    pub feed_mew_hash: Option<ActionHash>,
+}
+
+/// Push a notification signal through the mews zome, logging rather than failing when it
+/// cannot be sent, as signals are best effort
+pub fn push_notification(input: SendNotificationSignalInput) {
+    if let Err(err) = call_local_zome::<(), _>("mews", "send_notification_signal", input) {
+        warn!("Failed to send notification signal: {:?}", err);
+    }
+}
+
+/// A notification to add to another agent's notifications, about an action of mine
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+pub struct AddNotificationInput {
+    pub notification_type: NotificationType,
+    /// The agent to notify, or the author of the feed mew when None
+    pub recipient: Option<AgentPubKey>,
+    pub feed_mew_hash: Option<ActionHash>,
+    /// The action the notification is about, such as the like link or the response link
+    pub action_hash: ActionHash,
+    /// The recipient's own response to the same mew, for FollowedYarnResponded
+    pub yarn_response_hash: Option<ActionHash>,
+}
+
+/// Private choice of which notifications the agent receives
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq, Default)]
+pub struct NotificationPreferences {
+    pub disabled_types: Vec<NotificationType>,
+    pub muted_agents: Vec<AgentPubKey>,
+    /// Mews whose responses no longer notify as FollowedYarnResponded
+    pub muted_yarns: Vec<ActionHash>,
+}
+
+/// Private record of how far the agent has read their notifications
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct NotificationsRead {
+    /// Notifications at or before this timestamp have been read
+    pub last_read_timestamp: Timestamp,
+}
+
+/// Tags from the prefix index starting with the query
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+pub struct SearchTagsInput {
+    pub query: String,
+    pub limit: usize,
 }
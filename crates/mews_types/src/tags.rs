@@ -0,0 +1,66 @@
+pub const HASHTAG_SYMBOL: char = '#';
+pub const CASHTAG_SYMBOL: char = '$';
+pub const LINK_SYMBOL: char = '^';
+
+/// Hashtags (`#topic`) in a mew's text, deduplicated in order of appearance
+pub fn get_hashtags(text: &str) -> Vec<String> {
+    dedup_tags(parse_tags(text, HASHTAG_SYMBOL))
+}
+
+/// Cashtags (`$TICKER`) in a mew's text, deduplicated in order of appearance
+///
+/// A cashtag must start with a letter, so amounts like `$5` are not indexed.
+pub fn get_cashtags(text: &str) -> Vec<String> {
+    dedup_tags(
+        parse_tags(text, CASHTAG_SYMBOL)
+            .into_iter()
+            .filter(|cashtag| cashtag.chars().nth(1).map_or(false, char::is_alphabetic))
+            .collect(),
+    )
+}
+
+/// Words starting with `symbol` that are not preceded by a word character, including the symbol
+pub fn parse_tags(text: &str, symbol: char) -> Vec<&str> {
+    let mut tags = vec![];
+    let mut previous: Option<char> = None;
+    let mut indices = text.char_indices().peekable();
+
+    while let Some((start, c)) = indices.next() {
+        let at_word_boundary = previous.map_or(true, |p| !is_word_char(p));
+        previous = Some(c);
+        if c != symbol || !at_word_boundary {
+            continue;
+        }
+
+        let mut end = start + c.len_utf8();
+        while let Some((i, next)) = indices.peek().copied() {
+            if !is_word_char(next) {
+                break;
+            }
+            end = i + next.len_utf8();
+            previous = Some(next);
+            indices.next();
+        }
+
+        if end > start + c.len_utf8() {
+            tags.push(&text[start..end]);
+        }
+    }
+
+    tags
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn dedup_tags(tags: Vec<&str>) -> Vec<String> {
+    let mut deduped: Vec<String> = vec![];
+    for tag in tags {
+        if !deduped.iter().any(|t| t == tag) {
+            deduped.push(tag.to_string());
+        }
+    }
+
+    deduped
+}
@@ -1,5 +1,11 @@
 use agent_pins_integrity::*;
+use hc_call_utils::call_local_zome;
+use hc_link_type_utils::{resolve_link_type, ResolvedLinkType};
 use hdk::prelude::*;
+use mews_types::{
+    push_notification, AddNotificationInput, NotificationType, SendNotificationSignalInput,
+};
+use std::collections::HashSet;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct AddHashForPinnerInput {
@@ -8,18 +14,36 @@ pub struct AddHashForPinnerInput {
 }
 #[hdk_extern]
 pub fn add_hash_for_pinner(input: AddHashForPinnerInput) -> ExternResult<()> {
+    // New pins go after the pinner's existing ones
+    let position = match get_pin_links_ordered(input.base_pinner.clone())?
+        .into_iter()
+        .filter_map(|link| PinTag::try_from(link.tag).ok())
+        .map(|pin_tag| pin_tag.position)
+        .max()
+    {
+        Some(last_position) => {
+            last_position
+                .checked_add(1)
+                .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                    "No pin position left after your last pin"
+                ))))?
+        }
+        None => 0,
+    };
+
     create_link(
         input.base_pinner.clone(),
         input.target_hash.clone(),
         LinkTypes::PinnerToHashes,
-        (),
+        make_pin_tag(position)?,
     )?;
-    create_link(
-        input.target_hash,
+    let link_hash = create_link(
+        input.target_hash.clone(),
         input.base_pinner,
         LinkTypes::HashToPinners,
         (),
     )?;
+    notify_pin(NotificationType::MyMewPinned, input.target_hash, link_hash)?;
 
     Ok(())
 }
@@ -49,7 +73,8 @@ pub fn get_pinners_for_hash(hash: AnyLinkableHash) -> ExternResult<Vec<AgentPubK
 #[hdk_extern]
 pub fn get_pinner_links_for_hash(hash: AnyLinkableHash) -> ExternResult<Vec<Link>> {
     let mut links = get_links(hash, LinkTypes::HashToPinners, None)?;
-    links.dedup_by_key(|l| l.target.clone());
+    let mut seen_targets: HashSet<AnyLinkableHash> = HashSet::new();
+    links.retain(|link| seen_targets.insert(link.target.clone()));
 
     Ok(links)
 }
@@ -80,15 +105,58 @@ pub fn remove_hash_for_pinner(input: RemoveHashForPinnerInput) -> ExternResult<(
         if link.target.eq(&AnyLinkableHash::from(EntryHash::from(
             input.base_pinner.clone(),
         ))) {
-            delete_link(link.create_link_hash)?;
+            let delete_hash = delete_link(link.create_link_hash)?;
+            notify_pin(
+                NotificationType::MyMewUnpinned,
+                input.target_hash.clone(),
+                delete_hash,
+            )?;
         }
     }
 
     Ok(())
 }
 
+/// Add a pin or unpin to the mew author's notifications, and push it to them
+fn notify_pin(
+    notification_type: NotificationType,
+    hash: AnyLinkableHash,
+    action_hash: ActionHash,
+) -> ExternResult<()> {
+    // Only pins of mews notify their author
+    let mew_hash = match ActionHash::try_from(hash) {
+        Ok(mew_hash) => mew_hash,
+        Err(_) => return Ok(()),
+    };
+
+    call_local_zome::<(), AddNotificationInput>(
+        "mews",
+        "add_notification",
+        AddNotificationInput {
+            notification_type: notification_type.clone(),
+            recipient: None,
+            feed_mew_hash: Some(mew_hash.clone()),
+            action_hash,
+            yarn_response_hash: None,
+        },
+    )?;
+
+    push_notification(SendNotificationSignalInput {
+        notification_type,
+        recipient: None,
+        feed_mew_hash: Some(mew_hash),
+    });
+
+    Ok(())
+}
+
 #[hdk_extern]
 pub fn pin_hash(hash: AnyLinkableHash) -> ExternResult<()> {
+    // Pinning twice is a no-op, as validation rejects a second active pin
+    if is_hash_pinned(hash.clone())? {
+        return Ok(());
+    }
+
     add_hash_for_pinner(AddHashForPinnerInput {
         base_pinner: agent_info()?.agent_initial_pubkey,
         target_hash: hash,
@@ -110,3 +178,77 @@ pub fn is_hash_pinned(hash: AnyLinkableHash) -> ExternResult<bool> {
 
     Ok(is_pinned)
 }
+
+/// The agent's pinned hashes in the order they chose
+#[hdk_extern]
+pub fn get_pins_ordered(pinner: AgentPubKey) -> ExternResult<Vec<AnyLinkableHash>> {
+    let hashes: Vec<AnyLinkableHash> = get_pin_links_ordered(pinner)?
+        .into_iter()
+        .map(|link| link.target)
+        .collect();
+
+    Ok(hashes)
+}
+
+/// Put my pinned hashes in a new order, which must contain each of them once
+#[hdk_extern]
+pub fn reorder_pins(hashes: Vec<AnyLinkableHash>) -> ExternResult<()> {
+    let my_pubkey = agent_info()?.agent_initial_pubkey;
+    let links = get_pin_links_ordered(my_pubkey.clone())?;
+
+    let is_same_pins = hashes.len() == links.len()
+        && links.iter().all(|link| hashes.contains(&link.target))
+        && hashes
+            .iter()
+            .enumerate()
+            .all(|(i, hash)| !hashes[..i].contains(hash));
+    if !is_same_pins {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The new order must contain each of your pinned hashes once"
+        ))));
+    }
+
+    // Link tags can't be updated, so the pins are re-created in their new positions.
+    // Deleting them all first keeps the pinner within max_pins.
+    for link in links {
+        delete_link(link.create_link_hash)?;
+    }
+    for (position, hash) in hashes.into_iter().enumerate() {
+        create_link(
+            my_pubkey.clone(),
+            hash,
+            LinkTypes::PinnerToHashes,
+            make_pin_tag(position as u32)?,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// HashToPinners as resolved in this DNA, for zomes that can't depend on agent_pins_integrity
+#[hdk_extern]
+pub fn get_hash_to_pinners_link_type(_: ()) -> ExternResult<ResolvedLinkType> {
+    resolve_link_type("agent_pins_integrity", LinkTypes::HashToPinners)
+}
+
+/// PinnerToHashes links by position, then pins made before ordering by when they were made
+fn get_pin_links_ordered(pinner: AgentPubKey) -> ExternResult<Vec<Link>> {
+    let mut links = get_links(pinner, LinkTypes::PinnerToHashes, None)?;
+    links.sort_by_key(|link| {
+        let position = PinTag::try_from(link.tag.clone())
+            .ok()
+            .map(|pin_tag| pin_tag.position);
+
+        (position.is_none(), position, link.timestamp)
+    });
+
+    Ok(links)
+}
+
+fn make_pin_tag(position: u32) -> ExternResult<LinkTag> {
+    let tag: SerializedBytes = PinTag { position }
+        .try_into()
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Failed to serialize PinTag".into())))?;
+
+    Ok(LinkTag::from(tag.bytes().clone()))
+}
@@ -0,0 +1,65 @@
+use blocks_integrity::*;
+use hdk::prelude::*;
+use std::collections::HashSet;
+
+#[hdk_extern]
+pub fn block_agent(agent: AgentPubKey) -> ExternResult<()> {
+    let my_pubkey = agent_info()?.agent_initial_pubkey;
+    if agent == my_pubkey {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "You cannot block yourself"
+        ))));
+    }
+    if is_blocked_by_me(agent.clone())? {
+        return Ok(());
+    }
+
+    create_link(my_pubkey, agent, LinkTypes::AgentToBlocked, ())?;
+
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn unblock_agent(agent: AgentPubKey) -> ExternResult<()> {
+    let links = get_links(
+        agent_info()?.agent_initial_pubkey,
+        LinkTypes::AgentToBlocked,
+        None,
+    )?;
+
+    for link in links {
+        if link.target == AnyLinkableHash::from(agent.clone()) {
+            delete_link(link.create_link_hash)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn get_blocked_agents_for_agent(agent: AgentPubKey) -> ExternResult<Vec<AgentPubKey>> {
+    let links = get_links(agent, LinkTypes::AgentToBlocked, None)?;
+
+    Ok(get_distinct_target_agents(links))
+}
+
+#[hdk_extern]
+pub fn get_my_blocked_agents(_: ()) -> ExternResult<Vec<AgentPubKey>> {
+    get_blocked_agents_for_agent(agent_info()?.agent_initial_pubkey)
+}
+
+#[hdk_extern]
+pub fn is_blocked_by_me(agent: AgentPubKey) -> ExternResult<bool> {
+    Ok(get_my_blocked_agents(())?.contains(&agent))
+}
+
+/// The agents that links point to, each once and in link order
+pub fn get_distinct_target_agents(links: Vec<Link>) -> Vec<AgentPubKey> {
+    let mut seen_agents: HashSet<AgentPubKey> = HashSet::new();
+
+    links
+        .into_iter()
+        .filter_map(|link| AgentPubKey::try_from(link.target).ok())
+        .filter(|agent| seen_agents.insert(agent.clone()))
+        .collect()
+}
@@ -0,0 +1,55 @@
+use crate::agent_to_blocked::get_distinct_target_agents;
+use blocks_integrity::*;
+use hdk::prelude::*;
+
+/// Mute an agent, leaving their mews out of my feeds without blocking them
+#[hdk_extern]
+pub fn mute_agent(agent: AgentPubKey) -> ExternResult<()> {
+    let my_pubkey = agent_info()?.agent_initial_pubkey;
+    if agent == my_pubkey {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "You cannot mute yourself"
+        ))));
+    }
+    if is_muted_by_me(agent.clone())? {
+        return Ok(());
+    }
+
+    create_link(my_pubkey, agent, LinkTypes::AgentToMuted, ())?;
+
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn unmute_agent(agent: AgentPubKey) -> ExternResult<()> {
+    let links = get_links(
+        agent_info()?.agent_initial_pubkey,
+        LinkTypes::AgentToMuted,
+        None,
+    )?;
+
+    for link in links {
+        if link.target == AnyLinkableHash::from(agent.clone()) {
+            delete_link(link.create_link_hash)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn get_muted_agents_for_agent(agent: AgentPubKey) -> ExternResult<Vec<AgentPubKey>> {
+    let links = get_links(agent, LinkTypes::AgentToMuted, None)?;
+
+    Ok(get_distinct_target_agents(links))
+}
+
+#[hdk_extern]
+pub fn get_my_muted_agents(_: ()) -> ExternResult<Vec<AgentPubKey>> {
+    get_muted_agents_for_agent(agent_info()?.agent_initial_pubkey)
+}
+
+#[hdk_extern]
+pub fn is_muted_by_me(agent: AgentPubKey) -> ExternResult<bool> {
+    Ok(get_my_muted_agents(())?.contains(&agent))
+}
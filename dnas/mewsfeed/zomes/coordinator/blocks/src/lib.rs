@@ -0,0 +1,7 @@
+pub mod agent_to_blocked;
+pub mod agent_to_muted;
+use hdk::prelude::*;
+#[hdk_extern]
+pub fn init(_: ()) -> ExternResult<InitCallbackResult> {
+    Ok(InitCallbackResult::Pass)
+}
@@ -1,156 +1,204 @@
-use chrono::{Utc, NaiveDateTime, DateTime};
+use crate::mew_contract::*;
 use flow_integrity::*;
 use hdk::prelude::*;
-use regex_lite::Regex;
+use mews_types::{LinkTarget, Mew, MewType};
+use std::collections::HashMap;
+
+/// A mew of a yarn and its flow, or why the flow couldn't be parsed
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ParsedFlowMew {
+    pub mew_hash: ActionHash,
+    pub author: AgentPubKey,
+    pub timestamp: Timestamp,
+    pub flow: Result<FlowRequest, FlowParseError>,
+}
 
-// const RE_MENTION: &'static str = r"@\S+";
-// const RE_CASH_TAG: &'static str = r"\$([0-9]+)([A-Z]+)";
-// const RE_BETWEEN: &'static str = r"between ?([0-9]{4}\/[0-9]{2}\/[0-9]{2})-([0-9]{4}\/[0-9]{2}\/[0-9]{2})";
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProcessedThread {
+    /// The yarn's flow mews, mews that don't start with % are left out
+    pub flows: Vec<ParsedFlowMew>,
+    /// Latest revisions of the contracts for my flows in the yarn
+    pub contracts: Vec<ActionHash>,
+}
 
+/// Parse the flow mews of a yarn, given root first, and bring the contracts for my flows up to date
+///
+/// My requests are Requested from each agent they mention, my promises answering someone else's
+/// request are Promised, and my thanks for a delivered promise to my request are Thanked.
+/// Processing a yarn again is a no-op.
 #[hdk_extern]
-pub fn prompt_for_next_flow(msg: String) -> ExternResult<Vec<String>> {
-    // Strip nbsp
-    let msg = msg.replace("\u{00A0}", " ");
-    info!("Generating prompt for {:?}", msg);
-
-    if msg.is_empty() {
-        info!("Skipping empty input input");
-        return Ok(vec![]);
+pub fn process_thread(thread: Vec<ActionHash>) -> ExternResult<ProcessedThread> {
+    let my_pubkey = agent_info()?.agent_initial_pubkey;
+
+    let mut flows: Vec<ParsedFlowMew> = vec![];
+    let mut replies_to: HashMap<ActionHash, ActionHash> = HashMap::new();
+    let mut mentions: HashMap<ActionHash, Vec<AgentPubKey>> = HashMap::new();
+    for mew_hash in thread {
+        let record = get(mew_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
+            WasmErrorInner::Guest(String::from("Mew not found"))
+        ))?;
+        let mew: Mew = record
+            .entry()
+            .to_app_option()
+            .map_err(|e| wasm_error!(e))?
+            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                "Malformed mew"
+            ))))?;
+        if !is_flow_text(&mew.text) {
+            continue;
+        }
+
+        if let MewType::Reply(parent_hash) = mew.mew_type {
+            replies_to.insert(mew_hash.clone(), parent_hash);
+        }
+        mentions.insert(
+            mew_hash.clone(),
+            mew.links
+                .into_iter()
+                .filter_map(|link| match link {
+                    LinkTarget::Mention(agent) => Some(agent),
+                    _ => None,
+                })
+                .collect(),
+        );
+        flows.push(ParsedFlowMew {
+            mew_hash,
+            author: record.action().author().clone(),
+            timestamp: record.action().timestamp(),
+            flow: parse_flow(&mew.text),
+        });
     }
 
-    if !msg.starts_with("%") {
-        return Err(wasm_error!("First msg must start with %"));
+    let find_parent_flow = |mew_hash: &ActionHash, kind: FlowKind| {
+        replies_to.get(mew_hash).and_then(|parent_hash| {
+            flows.iter().find(|parsed| {
+                parsed.mew_hash == *parent_hash && is_flow_kind(&parsed.flow, kind.clone())
+            })
+        })
+    };
+
+    let mut contracts: Vec<ActionHash> = vec![];
+    for parsed in flows.iter().filter(|parsed| parsed.author == my_pubkey) {
+        match &parsed.flow {
+            Ok(FlowRequest {
+                kind: FlowKind::Request,
+                ..
+            }) => {
+                let promisers = mentions.get(&parsed.mew_hash).cloned().unwrap_or_default();
+                for promiser in promisers.into_iter().filter(|agent| *agent != my_pubkey) {
+                    let contract_hash =
+                        match get_contract_between(parsed.mew_hash.clone(), &my_pubkey, &promiser)?
+                        {
+                            Some((record, _)) => record.signed_action().as_hash().clone(),
+                            None => create_mew_contract(MewContract {
+                                requestor: my_pubkey.clone(),
+                                promiser,
+                                request_mew: parsed.mew_hash.clone(),
+                                response_mew: None,
+                                status: ContractStatus::Requested,
+                                deadline: get_flow_deadline(&parsed.flow),
+                                amount: get_flow_amount(&parsed.flow),
+                            })?,
+                        };
+                    contracts.push(contract_hash);
+                }
+            }
+            Ok(FlowRequest {
+                kind: FlowKind::Promise,
+                ..
+            }) => {
+                let request = match find_parent_flow(&parsed.mew_hash, FlowKind::Request) {
+                    Some(request) if request.author != my_pubkey => request,
+                    _ => continue,
+                };
+                // A late promise would be rejected, so it leaves the contract as it is
+                let deadline = get_flow_deadline(&request.flow);
+                if deadline.map_or(false, |deadline| parsed.timestamp >= deadline) {
+                    continue;
+                }
+                let contract_hash = match get_contract_between(
+                    request.mew_hash.clone(),
+                    &request.author,
+                    &my_pubkey,
+                )? {
+                    Some((record, mew_contract))
+                        if mew_contract.status == ContractStatus::Requested =>
+                    {
+                        update_mew_contract(
+                            record,
+                            mew_contract,
+                            ContractStatus::Promised,
+                            Some(parsed.mew_hash.clone()),
+                        )?
+                    }
+                    Some((record, _)) => record.signed_action().as_hash().clone(),
+                    None => create_mew_contract(MewContract {
+                        requestor: request.author.clone(),
+                        promiser: my_pubkey.clone(),
+                        request_mew: request.mew_hash.clone(),
+                        response_mew: Some(parsed.mew_hash.clone()),
+                        status: ContractStatus::Promised,
+                        deadline,
+                        amount: get_flow_amount(&request.flow),
+                    })?,
+                };
+                contracts.push(contract_hash);
+            }
+            Ok(FlowRequest {
+                kind: FlowKind::Thanks,
+                ..
+            }) => {
+                let promise = match find_parent_flow(&parsed.mew_hash, FlowKind::Promise) {
+                    Some(promise) if promise.author != my_pubkey => promise,
+                    _ => continue,
+                };
+                let request = match find_parent_flow(&promise.mew_hash, FlowKind::Request) {
+                    Some(request) if request.author == my_pubkey => request,
+                    _ => continue,
+                };
+                let contract_hash = match get_contract_between(
+                    request.mew_hash.clone(),
+                    &my_pubkey,
+                    &promise.author,
+                )? {
+                    Some((record, mew_contract))
+                        if mew_contract.status == ContractStatus::Delivered =>
+                    {
+                        update_mew_contract(record, mew_contract, ContractStatus::Thanked, None)?
+                    }
+                    Some((record, _)) => record.signed_action().as_hash().clone(),
+                    None => continue,
+                };
+                contracts.push(contract_hash);
+            }
+            Err(_) => {}
+        }
     }
 
-    let request_prefix = Regex::new(r"^%Request:.*").unwrap();
-    let promise_prefix = Regex::new(r"^%Promise:.*").unwrap();
-    let thanks_prefix = Regex::new(r"^%Thanks:.*").unwrap();
-
-    if request_prefix.is_match(msg.as_str()) {
-        prompt_request_msg(msg.as_str())
-    } else if promise_prefix.is_match(msg.as_str()) {
-        prompt_promise_msg(msg.as_str())
-    } else if thanks_prefix.is_match(msg.as_str()) {
-        // Nothing to prompt
-        Ok(vec![
-            "Loved your work".to_string(),
-        ])
-    } else {
-        Ok(vec![
-            "%Request:".to_string(),
-            "%Promise:".to_string(),
-            "%Thanks:".to_string(),
-        ])
-    }
+    Ok(ProcessedThread { flows, contracts })
 }
 
-// Expects input starting with "%Request:"
-fn prompt_request_msg(msg: &str) -> ExternResult<Vec<String>> {
-    info!("request prompt 1");
-
-    let request_start = Regex::new(r"^%Request: ?$").map_err(|e| {
-        error!("Bad regex: {:?}", e);
-        wasm_error!("Bad regex")
-    })?;
-
-    if request_start.is_match(msg) {
-        return Ok(vec![
-            "@username".to_string(),
-            "$1BTC".to_string(),
-            make_between_prompt()?,
-        ]);
-    }
-
-    info!("request prompt 2");
-
-    let request_with_mention = Regex::new(r"^%Request: ?(@\S+) ?$").unwrap();
-    if request_with_mention.is_match(msg) {
-        return Ok(vec![
-            "$1BTC".to_string(),
-            make_between_prompt()?,
-        ]);
-    }
-
-    info!("request prompt 3");
-
-    let request_with_mention_and_cash_tag = Regex::new(r"^%Request: ?(@\S+) \$([0-9\.]+)([A-Z]+) ?$").unwrap();
-    if request_with_mention_and_cash_tag.is_match(msg) {
-        return Ok(vec![
-            make_between_prompt()?,
-        ]);
-    }
-
-    info!("request prompt 4");
-
-    let request_skipped_mention_with_cash_tag = Regex::new(r"^%Request: \$([0-9\.]+)([A-Z]+) ?$").unwrap();
-    if request_skipped_mention_with_cash_tag.is_match(msg) {
-        return Ok(vec![
-            make_between_prompt()?,
-        ]);
-    }
-
-    info!("request prompt 5");
-
-    let request_with_between_no_msg = Regex::new(r"^%Request: ?(@\S+)? (\$([0-9\.]+)([A-Z]+))? ?between ?([0-9]{4}\/[0-9]{2}\/[0-9]{2})-([0-9]{4}\/[0-9]{2}\/[0-9]{2}) ?$").unwrap();
-    if request_with_between_no_msg.is_match(msg) {
-        return Ok(vec![
-            "Make me a coffee".to_string(),
-        ]);
+/// When a request's between dates are over
+fn get_flow_deadline(flow: &Result<FlowRequest, FlowParseError>) -> Option<Timestamp> {
+    match flow {
+        Ok(flow_request) => flow_request
+            .between
+            .as_ref()
+            .and_then(|between| between.deadline()),
+        Err(_) => None,
     }
-
-    Ok(vec![])
 }
 
-// Expects input starting with "%Promise:"
-fn prompt_promise_msg(msg: &str) -> ExternResult<Vec<String>> {
-    // Right at the start, can either prompt
-    let promise_start = Regex::new(r"^%Promise: ?$").unwrap();
-    if promise_start.is_match(msg) {
-        return Ok(vec![
-            "@username".to_string(),
-        ]);
-    }
-
-    let promise_with_mention = Regex::new(r"^%Promise: ?(@\S+) ?$").unwrap();
-    if promise_with_mention.is_match(msg) {
-        return Ok(vec![
-            "$1BTC".to_string(),
-            "I'm on it!".to_string(),
-        ]);
-    }
-
-    let promise_with_mention_and_cash_tag = Regex::new(r"^%Promise: ?(@\S+) \$([0-9\.]+)([A-Z]+) ?$").unwrap();
-    if promise_with_mention_and_cash_tag.is_match(msg) {
-        return Ok(vec![
-            "I'm on it!".to_string(),
-        ]);
+/// The amount written in a request
+fn get_flow_amount(flow: &Result<FlowRequest, FlowParseError>) -> Option<FlowAmount> {
+    match flow {
+        Ok(flow_request) => flow_request.amount.clone(),
+        Err(_) => None,
     }
-
-    Ok(vec![])
 }
 
-fn make_between_prompt() -> ExternResult<String> {
-    info!("Getting system time");
-    let now = sys_time()?;
-
-    info!("Got it");
-    let later = now.checked_add(&std::time::Duration::from_secs(60 * 60 * 24 * 5)).ok_or(wasm_error!("failed to add duration"))?;
-
-    info!("Made later");
-
-    let now_dt = DateTime::<Utc>::from_utc(NaiveDateTime::from_timestamp_opt(now.0 / 1_000_000, 0).unwrap(), Utc);
-    let later_dt = DateTime::<Utc>::from_utc(NaiveDateTime::from_timestamp_opt(later.0 / 1_000_000, 0).unwrap(), Utc);
-
-    info!("Do format");
-    Ok(format!("between {}-{}", now_dt.format("%Y/%m/%d").to_string(), later_dt.format("%Y/%m/%d").to_string()))
-}
-
-#[hdk_extern]
-pub fn process_thread(thread: Vec<String>) -> ExternResult<()> {
-
-    // let request_format = Regex::new(r"^%Request: ?(@\S+)? (\$([0-9\.]+)([A-Z]+))? ?between ?([0-9]{4}\/[0-9]{2}\/[0-9]{2})-([0-9]{4}\/[0-9]{2}\/[0-9]{2}) ?(.*)").unwrap();
-
-    Ok(())
+fn is_flow_kind(flow: &Result<FlowRequest, FlowParseError>, kind: FlowKind) -> bool {
+    matches!(flow, Ok(flow_request) if flow_request.kind == kind)
 }
 
 // #[hdk_extern]
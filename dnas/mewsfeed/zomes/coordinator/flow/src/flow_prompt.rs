@@ -0,0 +1,311 @@
+use crate::mew_contract::*;
+use chrono::{DateTime, Days, NaiveDate, TimeZone, Utc};
+use flow_integrity::*;
+use hc_call_utils::call_local_zome;
+use hdk::prelude::*;
+use mews_types::{Profile, SearchTagsInput};
+use std::collections::HashSet;
+
+const MAX_FLOW_PROMPTS: usize = 5;
+/// Nicknames are looked up in the profiles index once they are this long
+const MIN_MENTION_SEARCH_LENGTH: usize = 3;
+/// Most agents whose profile is read for mention prompts
+const MAX_MENTION_CANDIDATES: usize = 20;
+/// Tags are looked up in the prefix index once they are this long
+const MIN_CASHTAG_SEARCH_LENGTH: usize = 3;
+const BETWEEN_PROMPT_DAYS: u64 = 5;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum FlowPromptKind {
+    /// `%Request:`, `%Promise:` or `%Thanks:`
+    Kind,
+    /// The agent to link the mention to
+    Mention(AgentPubKey),
+    Cashtag,
+    Between,
+}
+
+/// A suggestion for the flow mew being written
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FlowPrompt {
+    /// Replaces the word being typed at the end of the text, which is empty after a space
+    pub insert_text: String,
+    pub display: String,
+    pub kind: FlowPromptKind,
+}
+
+/// Suggestions for the word being typed at the end of a flow mew, or for its next part
+///
+/// Mentions come from the agents I had contracts with and the profiles nickname index, cashtags
+/// from the tag prefix index and date ranges from today and the deadlines of my open promises.
+#[hdk_extern]
+pub fn prompt_for_next_flow(text: String) -> ExternResult<Vec<FlowPrompt>> {
+    let text = text.replace('\u{00A0}', " ");
+    if text.is_empty() {
+        return Ok(vec![]);
+    }
+    if !is_flow_text(&text) {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "A flow must start with %"
+        ))));
+    }
+
+    let (written, current_word) = split_current_word(&text);
+    if written.is_empty() {
+        return Ok(make_kind_prompts(current_word));
+    }
+
+    // Nothing is suggested for a malformed flow or once its free text has started
+    let flow = match parse_flow(written) {
+        Ok(flow) if flow.text.is_empty() => flow,
+        _ => return Ok(vec![]),
+    };
+    let can_mention = flow.mention.is_none() && flow.amount.is_none() && flow.between.is_none();
+    let can_add_amount =
+        flow.kind != FlowKind::Thanks && flow.amount.is_none() && flow.between.is_none();
+    let can_add_between = flow.kind == FlowKind::Request && flow.between.is_none();
+
+    let mut prompts = if current_word.is_empty() {
+        let mut prompts = vec![];
+        if can_mention {
+            prompts.extend(make_mention_prompts("")?);
+        }
+        if can_add_between {
+            prompts.extend(make_between_prompts()?);
+        }
+        prompts
+    } else if let Some(nickname_prefix) = current_word.strip_prefix('@') {
+        match can_mention {
+            true => make_mention_prompts(nickname_prefix)?,
+            false => vec![],
+        }
+    } else if current_word.starts_with('$') {
+        match can_add_amount {
+            true => make_cashtag_prompts(current_word)?,
+            false => vec![],
+        }
+    } else if can_add_between && "between".starts_with(current_word) {
+        make_between_prompts()?
+    } else {
+        vec![]
+    };
+    prompts.truncate(MAX_FLOW_PROMPTS);
+
+    Ok(prompts)
+}
+
+/// The text before the word being typed, and that word
+fn split_current_word(text: &str) -> (&str, &str) {
+    match text.rfind(' ') {
+        Some(index) => (text[..index].trim_end(), &text[index + 1..]),
+        None => ("", text),
+    }
+}
+
+fn make_kind_prompts(current_word: &str) -> Vec<FlowPrompt> {
+    ["%Request:", "%Promise:", "%Thanks:"]
+        .into_iter()
+        .filter(|kind| kind.starts_with(current_word))
+        .map(|kind| FlowPrompt {
+            insert_text: kind.to_string(),
+            display: kind.to_string(),
+            kind: FlowPromptKind::Kind,
+        })
+        .collect()
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct SearchAgentsInput {
+    nickname_filter: String,
+}
+
+/// Agents whose nickname starts with the prefix, the ones I had contracts with most recently first
+fn make_mention_prompts(nickname_prefix: &str) -> ExternResult<Vec<FlowPrompt>> {
+    let my_pubkey = agent_info()?.agent_initial_pubkey;
+    let mut agents = get_recent_interlocutors(my_pubkey.clone())?;
+    if nickname_prefix.chars().count() >= MIN_MENTION_SEARCH_LENGTH {
+        let found_agents = call_local_zome::<Vec<AgentPubKey>, SearchAgentsInput>(
+            "profiles",
+            "search_agents",
+            SearchAgentsInput {
+                nickname_filter: nickname_prefix.to_string(),
+            },
+        )?;
+        agents.extend(found_agents);
+    }
+
+    let mut seen_agents: HashSet<AgentPubKey> = HashSet::new();
+    let candidates: Vec<AgentPubKey> = agents
+        .into_iter()
+        .filter(|agent| *agent != my_pubkey && seen_agents.insert(agent.clone()))
+        .take(MAX_MENTION_CANDIDATES)
+        .collect();
+
+    let nickname_prefix = nickname_prefix.to_lowercase();
+    let mut prompts: Vec<FlowPrompt> = vec![];
+    for agent in candidates {
+        if prompts.len() >= MAX_FLOW_PROMPTS {
+            break;
+        }
+
+        let profile = match get_profile(agent.clone())? {
+            Some(profile) => profile,
+            None => continue,
+        };
+        if !profile
+            .nickname
+            .to_lowercase()
+            .starts_with(&nickname_prefix)
+        {
+            continue;
+        }
+
+        let mention = format!("@{}", profile.nickname);
+        prompts.push(FlowPrompt {
+            display: match profile.fields.get("Display Name") {
+                Some(display_name) => format!("{} ({})", display_name, mention),
+                None => mention.clone(),
+            },
+            insert_text: mention,
+            kind: FlowPromptKind::Mention(agent),
+        });
+    }
+
+    Ok(prompts)
+}
+
+/// The other parties of the contracts I committed or was asked for, most recent first
+fn get_recent_interlocutors(my_pubkey: AgentPubKey) -> ExternResult<Vec<AgentPubKey>> {
+    let my_contract_records = query(
+        ChainQueryFilter::new()
+            .entry_type(UnitEntryTypes::MewContract.try_into()?)
+            .include_entries(true),
+    )?;
+
+    let mut interlocutors: Vec<(Timestamp, AgentPubKey)> = vec![];
+    for record in my_contract_records {
+        let mew_contract = get_mew_contract(&record)?;
+        let other_party = match mew_contract.requestor == my_pubkey {
+            true => mew_contract.promiser,
+            false => mew_contract.requestor,
+        };
+        interlocutors.push((record.action().timestamp(), other_party));
+    }
+    for (record, mew_contract) in get_contracts_for_promiser(my_pubkey)? {
+        interlocutors.push((record.action().timestamp(), mew_contract.requestor));
+    }
+    interlocutors.sort_by(|a, b| b.0.cmp(&a.0));
+
+    Ok(interlocutors.into_iter().map(|(_, agent)| agent).collect())
+}
+
+fn get_profile(agent: AgentPubKey) -> ExternResult<Option<Profile>> {
+    let maybe_record =
+        call_local_zome::<Option<Record>, AgentPubKey>("profiles", "get_agent_profile", agent)?;
+
+    match maybe_record {
+        Some(record) => record.entry().to_app_option().map_err(|e| wasm_error!(e)),
+        None => Ok(None),
+    }
+}
+
+/// Amounts in the currencies of the indexed cashtags that start with the one being typed
+fn make_cashtag_prompts(current_word: &str) -> ExternResult<Vec<FlowPrompt>> {
+    let amount = &current_word[1..];
+    let currency_start = amount
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(amount.len());
+    let (value, currency_prefix) = amount.split_at(currency_start);
+    if currency_prefix.len() < MIN_CASHTAG_SEARCH_LENGTH {
+        return Ok(vec![]);
+    }
+    let value = match value.is_empty() {
+        true => "1",
+        false => value,
+    };
+
+    let cashtags = call_local_zome::<Vec<String>, SearchTagsInput>(
+        "mews",
+        "search_tags",
+        SearchTagsInput {
+            query: currency_prefix.to_string(),
+            limit: MAX_FLOW_PROMPTS,
+        },
+    )?;
+
+    let prompts = cashtags
+        .into_iter()
+        .filter_map(|cashtag| cashtag.strip_prefix('$').map(String::from))
+        .filter(|currency| {
+            currency.starts_with(currency_prefix)
+                && currency.chars().all(|c| c.is_ascii_uppercase())
+        })
+        .map(|currency| {
+            let amount = format!("${}{}", value, currency);
+            FlowPrompt {
+                insert_text: amount.clone(),
+                display: amount,
+                kind: FlowPromptKind::Cashtag,
+            }
+        })
+        .collect();
+
+    Ok(prompts)
+}
+
+/// Date ranges starting today, and starting once my open promises are due
+fn make_between_prompts() -> ExternResult<Vec<FlowPrompt>> {
+    let today = timestamp_to_utc(sys_time()?)?.date_naive();
+    let mut prompts = vec![make_between_prompt(
+        today,
+        format!("Next {} days", BETWEEN_PROMPT_DAYS),
+    )?];
+
+    let my_pubkey = agent_info()?.agent_initial_pubkey;
+    let latest_open_deadline = get_contracts_for_promiser(my_pubkey)?
+        .into_iter()
+        .filter(|(_, mew_contract)| mew_contract.status == ContractStatus::Promised)
+        .filter_map(|(_, mew_contract)| mew_contract.deadline)
+        .max();
+    if let Some(deadline) = latest_open_deadline {
+        let free_date = timestamp_to_utc(deadline)?.date_naive();
+        if free_date > today {
+            prompts.push(make_between_prompt(
+                free_date,
+                String::from("After your open promises"),
+            )?);
+        }
+    }
+
+    Ok(prompts)
+}
+
+fn make_between_prompt(start: NaiveDate, description: String) -> ExternResult<FlowPrompt> {
+    let end = start
+        .checked_add_days(Days::new(BETWEEN_PROMPT_DAYS))
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Date out of range"
+        ))))?;
+    let between = format!(
+        "between {}-{}",
+        start.format("%Y/%m/%d"),
+        end.format("%Y/%m/%d")
+    );
+
+    Ok(FlowPrompt {
+        display: format!("{} ({})", between, description),
+        insert_text: between,
+        kind: FlowPromptKind::Between,
+    })
+}
+
+/// The UTC date and time of a timestamp, such as one from sys_time
+pub fn timestamp_to_utc(timestamp: Timestamp) -> ExternResult<DateTime<Utc>> {
+    let (seconds, nanos) = timestamp.as_seconds_and_nanos();
+
+    Utc.timestamp_opt(seconds, nanos)
+        .single()
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Timestamp out of range"
+        ))))
+}
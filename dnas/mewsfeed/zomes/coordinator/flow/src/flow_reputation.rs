@@ -0,0 +1,95 @@
+use crate::mew_contract::*;
+use flow_integrity::*;
+use hdk::prelude::*;
+use std::collections::{BTreeMap, HashSet};
+
+/// How an agent kept their promises
+///
+/// Only public contract entries and their links are read, so anyone can recompute it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct FlowReputation {
+    /// Contracts the agent promised, whatever happened to them after
+    pub promised: usize,
+    /// Promises delivered, thanked or not
+    pub delivered: usize,
+    /// Jobs done, as recorded by the requestors who thanked the agent
+    pub thanked: usize,
+    /// Promises that were not delivered by their deadline, marked Expired or not
+    pub expired: usize,
+    /// Total amount of the thanked requests by currency, as exact decimals such as `1.5`
+    pub totals: BTreeMap<String, String>,
+}
+
+#[hdk_extern]
+pub fn get_flow_reputation(agent: AgentPubKey) -> ExternResult<FlowReputation> {
+    let now = sys_time()?;
+    let mut reputation = FlowReputation::default();
+
+    for (_, mew_contract) in get_contracts_for_promiser(agent.clone())? {
+        if mew_contract.response_mew.is_none() {
+            continue;
+        }
+
+        reputation.promised += 1;
+        match mew_contract.status {
+            ContractStatus::Delivered | ContractStatus::Thanked => reputation.delivered += 1,
+            ContractStatus::Expired => reputation.expired += 1,
+            ContractStatus::Promised
+                if mew_contract
+                    .deadline
+                    .map_or(false, |deadline| now >= deadline) =>
+            {
+                reputation.expired += 1
+            }
+            _ => {}
+        }
+    }
+
+    // A job done linked more than once still counts once
+    let mut counted_contracts: HashSet<ActionHash> = HashSet::new();
+    for record in get_jobs_done_for_promiser(agent)? {
+        if !counted_contracts.insert(get_original_contract_hash(&record)?) {
+            continue;
+        }
+        let mew_contract = get_mew_contract(&record)?;
+        reputation.thanked += 1;
+
+        if let Some(amount) = mew_contract.amount {
+            let total = match reputation.totals.get(&amount.currency) {
+                Some(total) => add_decimals(total, &amount.value)?,
+                None => amount.value,
+            };
+            reputation.totals.insert(amount.currency, total);
+        }
+    }
+
+    Ok(reputation)
+}
+
+/// Sum of two amounts written like `1.5`, without rounding them
+fn add_decimals(a: &str, b: &str) -> ExternResult<String> {
+    let (a_whole, a_fraction) = a.split_once('.').unwrap_or((a, ""));
+    let (b_whole, b_fraction) = b.split_once('.').unwrap_or((b, ""));
+    let scale = a_fraction.len().max(b_fraction.len());
+    let to_scaled = |whole: &str, fraction: &str| {
+        format!("{}{:0<scale$}", whole, fraction, scale = scale)
+            .parse::<u128>()
+            .ok()
+    };
+
+    let sum = to_scaled(a_whole, a_fraction)
+        .zip(to_scaled(b_whole, b_fraction))
+        .and_then(|(a, b)| a.checked_add(b))
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Amount out of range"
+        ))))?;
+
+    let digits = format!("{:0>width$}", sum, width = scale + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - scale);
+    let fraction = fraction.trim_end_matches('0');
+
+    Ok(match fraction.is_empty() {
+        true => whole.to_string(),
+        false => format!("{}.{}", whole, fraction),
+    })
+}
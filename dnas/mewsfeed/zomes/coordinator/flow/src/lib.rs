@@ -1,5 +1,8 @@
 use hdk::prelude::*;
 pub mod flow;
+pub mod flow_prompt;
+pub mod flow_reputation;
+pub mod mew_contract;
 
 #[hdk_extern]
 pub fn init(_: ()) -> ExternResult<InitCallbackResult> {
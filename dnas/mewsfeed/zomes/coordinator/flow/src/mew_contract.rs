@@ -0,0 +1,226 @@
+use flow_integrity::*;
+use hdk::prelude::*;
+
+/// Latest revision of a contract, given any of its revisions
+///
+/// Each status change updates the previous revision, so this follows the updates that move the
+/// contract to one of its next statuses, taking the earliest when there are several.
+pub fn get_latest_contract_record(contract_hash: ActionHash) -> ExternResult<Option<Record>> {
+    let mut latest = match get_details(contract_hash, GetOptions::default())? {
+        Some(Details::Record(details)) => details,
+        _ => return Ok(None),
+    };
+    while let Some(next) = get_next_contract_revision(&latest)? {
+        latest = next;
+    }
+
+    Ok(Some(latest.record))
+}
+
+/// The earliest update of a contract revision to one of its next statuses
+fn get_next_contract_revision(details: &RecordDetails) -> ExternResult<Option<RecordDetails>> {
+    let next_statuses = get_mew_contract(&details.record)?.status.next_statuses();
+    let mut updates = details.updates.clone();
+    updates.sort_by_key(|update| (update.action().timestamp(), update.as_hash().clone()));
+
+    for update in updates {
+        if let Some(Details::Record(update_details)) =
+            get_details(update.as_hash().clone(), GetOptions::default())?
+        {
+            if next_statuses.contains(&get_mew_contract(&update_details.record)?.status) {
+                return Ok(Some(update_details));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Hash of the create action of a contract, given any of its revisions
+pub fn get_original_contract_hash(record: &Record) -> ExternResult<ActionHash> {
+    let mut action = record.signed_action().clone();
+    while let Action::Update(update) = action.action() {
+        action = get(
+            update.original_action_address.clone(),
+            GetOptions::default(),
+        )?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find a previous revision of the contract"
+        ))))?
+        .signed_action()
+        .clone();
+    }
+
+    Ok(action.as_hash().clone())
+}
+
+#[hdk_extern]
+pub fn get_latest_mew_contract(contract_hash: ActionHash) -> ExternResult<Option<Record>> {
+    get_latest_contract_record(contract_hash)
+}
+
+/// Contracts committed for a request mew, at their latest revision
+#[hdk_extern]
+pub fn get_contracts_for_request(request_mew: ActionHash) -> ExternResult<Vec<Record>> {
+    let links = get_links(request_mew, LinkTypes::MewToContracts, None)?;
+
+    let mut records: Vec<Record> = vec![];
+    for link in links {
+        let contract_hash = ActionHash::try_from(link.target).map_err(|err| wasm_error!(err))?;
+        if let Some(record) = get_latest_contract_record(contract_hash)? {
+            records.push(record);
+        }
+    }
+
+    Ok(records)
+}
+
+/// Thanked contracts the agent promised, as recorded by their requestors
+#[hdk_extern]
+pub fn get_jobs_done_for_promiser(promiser: AgentPubKey) -> ExternResult<Vec<Record>> {
+    let links = get_links(promiser, LinkTypes::JobsDone, None)?;
+    let get_input: Vec<GetInput> = links
+        .into_iter()
+        .filter_map(|link| ActionHash::try_from(link.target).ok())
+        .map(|hash| GetInput::new(hash.into(), GetOptions::default()))
+        .collect();
+
+    let records: Vec<Record> = HDK
+        .with(|hdk| hdk.borrow().get(get_input))?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    Ok(records)
+}
+
+/// Contracts the agent was asked for or promised, at their latest revision
+pub fn get_contracts_for_promiser(
+    promiser: AgentPubKey,
+) -> ExternResult<Vec<(Record, MewContract)>> {
+    let links = get_links(promiser, LinkTypes::PromiserToContracts, None)?;
+
+    let mut contracts: Vec<(Record, MewContract)> = vec![];
+    for link in links {
+        let contract_hash = ActionHash::try_from(link.target).map_err(|err| wasm_error!(err))?;
+        if let Some(record) = get_latest_contract_record(contract_hash)? {
+            let mew_contract = get_mew_contract(&record)?;
+            contracts.push((record, mew_contract));
+        }
+    }
+
+    Ok(contracts)
+}
+
+/// The agent's promises that weren't delivered by their deadline, at their latest revision
+#[hdk_extern]
+pub fn get_overdue_contracts(promiser: AgentPubKey) -> ExternResult<Vec<Record>> {
+    let now = sys_time()?;
+    let records: Vec<Record> = get_contracts_for_promiser(promiser)?
+        .into_iter()
+        .filter(|(_, mew_contract)| {
+            mew_contract.status == ContractStatus::Promised
+                && mew_contract
+                    .deadline
+                    .map_or(false, |deadline| now >= deadline)
+        })
+        .map(|(record, _)| record)
+        .collect();
+
+    Ok(records)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateContractStatusInput {
+    /// Any revision of the contract, the latest one is updated
+    pub contract_hash: ActionHash,
+    pub status: ContractStatus,
+}
+#[hdk_extern]
+pub fn update_contract_status(input: UpdateContractStatusInput) -> ExternResult<ActionHash> {
+    let record = get_latest_contract_record(input.contract_hash)?.ok_or(wasm_error!(
+        WasmErrorInner::Guest(String::from("Could not find the contract"))
+    ))?;
+    let mew_contract = get_mew_contract(&record)?;
+
+    if input.status == ContractStatus::Promised {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Promise by replying to the request with a %Promise"
+        ))));
+    }
+    if !mew_contract.status.next_statuses().contains(&input.status) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "A contract cannot go from {:?} to {:?}",
+            mew_contract.status, input.status
+        ))));
+    }
+
+    update_mew_contract(record, mew_contract, input.status, None)
+}
+
+pub fn get_mew_contract(record: &Record) -> ExternResult<MewContract> {
+    record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Malformed contract"
+        ))))
+}
+
+/// The contract between a requestor and promiser for a request mew, at its latest revision
+pub fn get_contract_between(
+    request_mew: ActionHash,
+    requestor: &AgentPubKey,
+    promiser: &AgentPubKey,
+) -> ExternResult<Option<(Record, MewContract)>> {
+    for record in get_contracts_for_request(request_mew)? {
+        let mew_contract = get_mew_contract(&record)?;
+        if mew_contract.requestor == *requestor && mew_contract.promiser == *promiser {
+            return Ok(Some((record, mew_contract)));
+        }
+    }
+
+    Ok(None)
+}
+
+pub fn create_mew_contract(mew_contract: MewContract) -> ExternResult<ActionHash> {
+    let contract_hash = create_entry(EntryTypes::MewContract(mew_contract.clone()))?;
+    create_link(
+        mew_contract.request_mew,
+        contract_hash.clone(),
+        LinkTypes::MewToContracts,
+        (),
+    )?;
+    create_link(
+        mew_contract.promiser,
+        contract_hash.clone(),
+        LinkTypes::PromiserToContracts,
+        (),
+    )?;
+
+    Ok(contract_hash)
+}
+
+/// Move the latest revision of a contract to a new status, recording a job done for its promiser
+/// once thanked
+pub fn update_mew_contract(
+    record: Record,
+    mew_contract: MewContract,
+    status: ContractStatus,
+    response_mew: Option<ActionHash>,
+) -> ExternResult<ActionHash> {
+    let promiser = mew_contract.promiser.clone();
+    let updated_contract = MewContract {
+        response_mew: response_mew.or(mew_contract.response_mew),
+        status: status.clone(),
+        ..mew_contract
+    };
+    let contract_hash = update_entry(record.signed_action().as_hash().clone(), &updated_contract)?;
+
+    if status == ContractStatus::Thanked {
+        create_link(promiser, contract_hash.clone(), LinkTypes::JobsDone, ())?;
+    }
+
+    Ok(contract_hash)
+}
@@ -0,0 +1,260 @@
+use follows_integrity::*;
+use hc_call_utils::call_local_zome;
+use hdk::prelude::*;
+use mews_types::{push_notification, NotificationType, Profile, SendNotificationSignalInput};
+
+/// The agent's latest profile, with its action hash
+pub fn get_agent_profile(agent: AgentPubKey) -> ExternResult<Option<(ActionHash, Profile)>> {
+    let maybe_record =
+        call_local_zome::<Option<Record>, AgentPubKey>("profiles", "get_agent_profile", agent)?;
+
+    match maybe_record {
+        Some(record) => {
+            let profile = record
+                .entry()
+                .to_app_option::<Profile>()
+                .map_err(|e| wasm_error!(e))?
+                .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                    "Malformed profile"
+                ))))?;
+
+            Ok(Some((record.action_address().clone(), profile)))
+        }
+        None => Ok(None),
+    }
+}
+
+#[hdk_extern]
+pub fn is_agent_protected(agent: AgentPubKey) -> ExternResult<bool> {
+    Ok(match get_agent_profile(agent)? {
+        Some((_, profile)) => is_profile_protected(&profile.fields),
+        None => false,
+    })
+}
+
+/// Whether I can see a protected agent's mews and follow lists, as the agent or one of their followers
+///
+/// Only follows the agent accepted count, since a follow made while the agent wasn't protected
+/// has no acceptance.
+pub fn can_see_agent(agent: AgentPubKey) -> ExternResult<bool> {
+    let my_pubkey = agent_info()?.agent_initial_pubkey;
+    if agent == my_pubkey || !is_agent_protected(agent.clone())? {
+        return Ok(true);
+    }
+
+    let is_accepted_follower = get_links(agent, LinkTypes::CreatorToFollowers, None)?
+        .into_iter()
+        .any(|link| {
+            link.author == my_pubkey
+                && CreatorToFollowersTag::try_from(link.tag)
+                    .map_or(false, |tag| tag.acceptance.is_some())
+        });
+
+    Ok(is_accepted_follower)
+}
+
+/// The agents whose mews I can see, leaving out protected agents I don't follow
+#[hdk_extern]
+pub fn get_visible_agents(agents: Vec<AgentPubKey>) -> ExternResult<Vec<AgentPubKey>> {
+    let mut visible_agents: Vec<AgentPubKey> = vec![];
+    for agent in agents {
+        if can_see_agent(agent.clone())? {
+            visible_agents.push(agent);
+        }
+    }
+
+    Ok(visible_agents)
+}
+
+/// Tag for my CreatorToFollowers link, referencing the creator's chain head, their latest profile
+/// up to it and their acceptance when protected
+pub fn make_creator_to_followers_tag(creator: AgentPubKey) -> ExternResult<LinkTag> {
+    let creator_chain_head = get_agent_activity(
+        creator.clone(),
+        ChainQueryFilter::new(),
+        ActivityRequest::Full,
+    )?
+    .valid_activity
+    .into_iter()
+    .max_by_key(|(action_seq, _)| *action_seq)
+    .map(|(_, hash)| hash)
+    .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+        "Could not find the creator's chain"
+    ))))?;
+
+    // Same lookup as validation, so the tag references the profile validators expect
+    let creator_activity = must_get_agent_activity(
+        creator.clone(),
+        ChainFilter::new(creator_chain_head.clone()),
+    )?;
+    let latest_profile = get_latest_profile(&creator_activity)?;
+    let is_protected = latest_profile
+        .as_ref()
+        .map_or(false, |(_, fields)| is_profile_protected(fields));
+    let acceptance = match is_protected {
+        true => Some(get_my_follow_acceptance(creator)?.ok_or(wasm_error!(
+            WasmErrorInner::Guest(String::from(
+                "This account is protected, send a follow request instead"
+            ))
+        ))?),
+        false => None,
+    };
+
+    let tag: SerializedBytes = CreatorToFollowersTag {
+        creator_chain_head,
+        creator_profile: latest_profile.map(|(hash, _)| hash),
+        acceptance,
+    }
+    .try_into()
+    .map_err(|_| {
+        wasm_error!(WasmErrorInner::Guest(
+            "Failed to serialize CreatorToFollowersTag".into()
+        ))
+    })?;
+
+    Ok(LinkTag::from(tag.bytes().clone()))
+}
+
+/// The creator's acceptance of any of my follow requests to them
+fn get_my_follow_acceptance(creator: AgentPubKey) -> ExternResult<Option<ActionHash>> {
+    let my_follow_requests = query(
+        ChainQueryFilter::new()
+            .entry_type(UnitEntryTypes::FollowRequest.try_into()?)
+            .include_entries(true),
+    )?;
+
+    for record in my_follow_requests.into_iter().rev() {
+        let follow_request: Option<FollowRequest> =
+            record.entry().to_app_option().map_err(|e| wasm_error!(e))?;
+        if follow_request.map(|request| request.creator) != Some(creator.clone()) {
+            continue;
+        }
+
+        let links = get_links(
+            record.action_address().clone(),
+            LinkTypes::FollowRequestToAcceptances,
+            None,
+        )?;
+        if let Some(link) = links.into_iter().find(|link| link.author == creator) {
+            return Ok(Some(
+                ActionHash::try_from(link.target).map_err(|err| wasm_error!(err))?,
+            ));
+        }
+    }
+
+    Ok(None)
+}
+
+#[hdk_extern]
+pub fn request_to_follow(creator: AgentPubKey) -> ExternResult<ActionHash> {
+    let follow_request_hash = create_entry(EntryTypes::FollowRequest(FollowRequest {
+        creator: creator.clone(),
+    }))?;
+    create_link(
+        creator.clone(),
+        follow_request_hash.clone(),
+        LinkTypes::CreatorToFollowRequests,
+        (),
+    )?;
+
+    push_notification(SendNotificationSignalInput {
+        notification_type: NotificationType::MyAgentFollowRequested,
+        recipient: Some(creator),
+        feed_mew_hash: None,
+    });
+
+    Ok(follow_request_hash)
+}
+
+/// Pending follow requests to me, oldest first
+#[hdk_extern]
+pub fn get_my_follow_requests(_: ()) -> ExternResult<Vec<Record>> {
+    let mut links = get_links(
+        agent_info()?.agent_initial_pubkey,
+        LinkTypes::CreatorToFollowRequests,
+        None,
+    )?;
+    links.sort_by_key(|link| link.timestamp);
+
+    let get_input: Vec<GetInput> = links
+        .into_iter()
+        .filter_map(|link| ActionHash::try_from(link.target).ok())
+        .map(|hash| GetInput::new(hash.into(), GetOptions::default()))
+        .collect();
+    let records: Vec<Record> = HDK
+        .with(|hdk| hdk.borrow().get(get_input))?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    Ok(records)
+}
+
+#[hdk_extern]
+pub fn accept_follow_request(follow_request_hash: ActionHash) -> ExternResult<ActionHash> {
+    let follower = get_follow_request_to_me(follow_request_hash.clone())?;
+
+    let follow_acceptance_hash = create_entry(EntryTypes::FollowAcceptance(FollowAcceptance {
+        follow_request: follow_request_hash.clone(),
+        follower: follower.clone(),
+    }))?;
+    create_link(
+        follow_request_hash.clone(),
+        follow_acceptance_hash.clone(),
+        LinkTypes::FollowRequestToAcceptances,
+        (),
+    )?;
+    remove_follow_request_links(follow_request_hash)?;
+
+    push_notification(SendNotificationSignalInput {
+        notification_type: NotificationType::MyFollowRequestAccepted,
+        recipient: Some(follower),
+        feed_mew_hash: None,
+    });
+
+    Ok(follow_acceptance_hash)
+}
+
+#[hdk_extern]
+pub fn deny_follow_request(follow_request_hash: ActionHash) -> ExternResult<()> {
+    get_follow_request_to_me(follow_request_hash.clone())?;
+
+    remove_follow_request_links(follow_request_hash)
+}
+
+/// The follower of a follow request to me
+fn get_follow_request_to_me(follow_request_hash: ActionHash) -> ExternResult<AgentPubKey> {
+    let record = get(follow_request_hash, GetOptions::default())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest(String::from("Could not find the follow request"))
+    ))?;
+    let follow_request: FollowRequest = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Malformed follow request"
+        ))))?;
+    if follow_request.creator != agent_info()?.agent_initial_pubkey {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "You can only answer follow requests to you"
+        ))));
+    }
+
+    Ok(record.action().author().clone())
+}
+
+fn remove_follow_request_links(follow_request_hash: ActionHash) -> ExternResult<()> {
+    let links = get_links(
+        agent_info()?.agent_initial_pubkey,
+        LinkTypes::CreatorToFollowRequests,
+        None,
+    )?;
+
+    for link in links {
+        if link.target == AnyLinkableHash::from(follow_request_hash.clone()) {
+            delete_link(link.create_link_hash)?;
+        }
+    }
+
+    Ok(())
+}
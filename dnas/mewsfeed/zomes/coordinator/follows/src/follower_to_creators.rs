@@ -1,21 +1,36 @@
+use crate::follow_requests::{can_see_agent, make_creator_to_followers_tag};
 use follows_integrity::*;
 use follows_types::*;
+use hc_call_utils::call_local_zome;
 use hc_link_pagination::paginate_by_agentpubkey;
+use hc_link_type_utils::{resolve_link_type, ResolvedLinkType};
 use hdk::prelude::*;
+use mews_types::{
+    push_notification, AddNotificationInput, NotificationType, SendNotificationSignalInput,
+};
+use std::collections::HashSet;
 
 #[hdk_extern]
 pub fn add_creator_for_follower(input: AddCreatorForFollowerInput) -> ExternResult<()> {
+    // Made first so that following a protected creator without their acceptance fails before any link is created
+    let creator_to_followers_tag = make_creator_to_followers_tag(input.target_creator.clone())?;
+
     create_link(
         input.base_follower.clone(),
         input.target_creator.clone(),
         LinkTypes::FollowerToCreators,
         (),
     )?;
-    create_link(
-        input.target_creator,
+    let link_hash = create_link(
+        input.target_creator.clone(),
         input.base_follower,
         LinkTypes::CreatorToFollowers,
-        (),
+        creator_to_followers_tag,
+    )?;
+    notify_follow(
+        NotificationType::MyAgentFollowed,
+        input.target_creator,
+        link_hash,
     )?;
 
     Ok(())
@@ -25,6 +40,11 @@ pub fn add_creator_for_follower(input: AddCreatorForFollowerInput) -> ExternResu
 pub fn get_creators_for_follower(
     input: GetCreatorsForFollowerInput,
 ) -> ExternResult<Vec<AgentPubKey>> {
+    // Follow lists of protected agents are only shown to them and their followers
+    if !can_see_agent(input.follower.clone())? {
+        return Ok(vec![]);
+    }
+
     let links = get_links(input.follower, LinkTypes::FollowerToCreators, None)?;
     let links_page = paginate_by_agentpubkey(links, input.page)?;
 
@@ -56,23 +76,14 @@ pub fn get_followers_for_creator(
 pub fn count_creators_for_follower(follower: AgentPubKey) -> ExternResult<usize> {
     let query = LinkQuery::new(
         follower,
-        LinkTypeFilter::single_type(
-            ZomeIndex(2),
-            LinkType(0), // LinkTypes::FollowerToCreators
-        ),
+        resolve_link_type("follows_integrity", LinkTypes::FollowerToCreators)?.filter(),
     );
     count_links(query)
 }
 
 #[hdk_extern]
 pub fn count_followers_for_creator(creator: AgentPubKey) -> ExternResult<usize> {
-    let query = LinkQuery::new(
-        creator,
-        LinkTypeFilter::single_type(
-            ZomeIndex(2),
-            LinkType(1), // LinkTypes::CreatorToFollowers
-        ),
-    );
+    let query = LinkQuery::new(creator, get_creator_to_followers_link_type(())?.filter());
     count_links(query)
 }
 
@@ -80,8 +91,13 @@ pub fn count_followers_for_creator(creator: AgentPubKey) -> ExternResult<usize>
 pub fn get_follower_links_for_creator(
     input: GetFollowersForCreatorInput,
 ) -> ExternResult<Vec<Link>> {
+    if !can_see_agent(input.creator.clone())? {
+        return Ok(vec![]);
+    }
+
     let mut links = get_links(input.creator, LinkTypes::CreatorToFollowers, None)?;
-    links.dedup_by_key(|l| l.target.clone());
+    let mut seen_targets: HashSet<AnyLinkableHash> = HashSet::new();
+    links.retain(|link| seen_targets.insert(link.target.clone()));
     let links_page = paginate_by_agentpubkey(links, input.page)?;
 
     Ok(links_page)
@@ -120,13 +136,45 @@ pub fn remove_creator_for_follower(input: RemoveCreatorForFollowerInput) -> Exte
         let entry_hash =
             EntryHash::try_from(link.target.clone()).map_err(|err| wasm_error!(err))?;
         if AgentPubKey::from(entry_hash).eq(&input.base_follower) {
-            delete_link(link.create_link_hash)?;
+            let delete_hash = delete_link(link.create_link_hash)?;
+            notify_follow(
+                NotificationType::MyAgentUnfollowed,
+                input.target_creator.clone(),
+                delete_hash,
+            )?;
         }
     }
 
     Ok(())
 }
 
+/// Add a follow or unfollow to the creator's notifications, and push it to them
+fn notify_follow(
+    notification_type: NotificationType,
+    recipient: AgentPubKey,
+    action_hash: ActionHash,
+) -> ExternResult<()> {
+    call_local_zome::<(), AddNotificationInput>(
+        "mews",
+        "add_notification",
+        AddNotificationInput {
+            notification_type: notification_type.clone(),
+            recipient: Some(recipient.clone()),
+            feed_mew_hash: None,
+            action_hash,
+            yarn_response_hash: None,
+        },
+    )?;
+
+    push_notification(SendNotificationSignalInput {
+        notification_type,
+        recipient: Some(recipient),
+        feed_mew_hash: None,
+    });
+
+    Ok(())
+}
+
 #[hdk_extern]
 pub fn follow(agent: AgentPubKey) -> ExternResult<()> {
     add_creator_for_follower(AddCreatorForFollowerInput {
@@ -142,3 +190,9 @@ pub fn unfollow(agent: AgentPubKey) -> ExternResult<()> {
         target_creator: agent,
     })
 }
+
+/// CreatorToFollowers as resolved in this DNA, for zomes that can't depend on follows_integrity
+#[hdk_extern]
+pub fn get_creator_to_followers_link_type(_: ()) -> ExternResult<ResolvedLinkType> {
+    resolve_link_type("follows_integrity", LinkTypes::CreatorToFollowers)
+}
@@ -1,4 +1,5 @@
 use hdk::prelude::*;
+pub mod follow_requests;
 pub mod follower_to_creators;
 
 #[hdk_extern]
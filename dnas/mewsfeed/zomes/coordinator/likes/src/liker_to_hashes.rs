@@ -1,26 +1,45 @@
+use hc_call_utils::call_local_zome;
+use hc_link_type_utils::{resolve_link_type, ResolvedLinkType};
 use hdk::prelude::*;
 use likes_integrity::*;
+use mews_types::{
+    push_notification, AddNotificationInput, NotificationType, ReactionSummary,
+    SendNotificationSignalInput,
+};
+use std::collections::{BTreeMap, HashSet};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct AddHashForLikerInput {
     pub base_liker: AgentPubKey,
     pub target_hash: AnyLinkableHash,
+    /// A lick when None
+    pub reaction: Option<String>,
 }
 #[hdk_extern]
 pub fn add_hash_for_liker(input: AddHashForLikerInput) -> ExternResult<()> {
+    let is_lick = input
+        .reaction
+        .as_ref()
+        .map_or(true, |reaction| reaction == LICK_REACTION);
+    let tag = make_reaction_tag(input.reaction)?;
+
     create_link(
         input.base_liker.clone(),
         input.target_hash.clone(),
         LinkTypes::LikerToHashes,
-        (),
+        tag.clone(),
     )?;
-    create_link(
-        input.target_hash,
+    let link_hash = create_link(
+        input.target_hash.clone(),
         input.base_liker,
         LinkTypes::HashToLikers,
-        (),
+        tag,
     )?;
 
+    if is_lick {
+        notify_lick(NotificationType::MyMewLicked, input.target_hash, link_hash)?;
+    }
+
     Ok(())
 }
 
@@ -28,7 +47,11 @@ pub fn add_hash_for_liker(input: AddHashForLikerInput) -> ExternResult<()> {
 pub fn get_hashes_for_liker(liker: AgentPubKey) -> ExternResult<Vec<AnyLinkableHash>> {
     let links = get_links(liker, LinkTypes::LikerToHashes, None)?;
 
-    let hashes: Vec<AnyLinkableHash> = links.into_iter().map(|link| link.target).collect();
+    let hashes: Vec<AnyLinkableHash> = links
+        .into_iter()
+        .filter(|link| is_link_reaction(link, LICK_REACTION))
+        .map(|link| link.target)
+        .collect();
 
     Ok(hashes)
 }
@@ -47,15 +70,7 @@ pub fn get_likers_for_hash(hash: AnyLinkableHash) -> ExternResult<Vec<AgentPubKe
 
 #[hdk_extern]
 pub fn count_likers_for_hash(hash: AnyLinkableHash) -> ExternResult<usize> {
-    let query = LinkQuery::new(
-        hash,
-        LinkTypeFilter::single_type(
-            ZomeIndex(3),
-            LinkType(1), // LinkTypes::HashToLikers
-        ),
-    );
-
-    count_links(query)
+    Ok(get_liker_links_for_hash(hash)?.len())
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -65,24 +80,20 @@ pub struct IsLikerForHashInput {
 }
 #[hdk_extern]
 pub fn is_liker_for_hash(input: IsLikerForHashInput) -> ExternResult<bool> {
-    let query = LinkQuery::new(
-        input.hash,
-        LinkTypeFilter::single_type(
-            ZomeIndex(3),
-            LinkType(1), // LinkTypes::HashToLikers
-        ),
-    )
-    .author(input.liker);
-
-    let count = count_links(query)?;
+    let liker = AnyLinkableHash::from(input.liker);
+    let is_liker = get_liker_links_for_hash(input.hash)?
+        .iter()
+        .any(|link| link.target == liker);
 
-    Ok(count > 0)
+    Ok(is_liker)
 }
 
+/// HashToLikers links of licks, one per liker
 #[hdk_extern]
 pub fn get_liker_links_for_hash(hash: AnyLinkableHash) -> ExternResult<Vec<Link>> {
-    let mut links = get_links(hash, LinkTypes::HashToLikers, None)?;
-    links.dedup_by_key(|l| l.target.clone());
+    let mut links = get_reaction_links_for_hash(hash, Some(LICK_REACTION))?;
+    let mut seen_targets: HashSet<AnyLinkableHash> = HashSet::new();
+    links.retain(|link| seen_targets.insert(link.target.clone()));
 
     Ok(links)
 }
@@ -96,47 +107,258 @@ pub fn get_liker_link_details_for_hash(hash: AnyLinkableHash) -> ExternResult<Li
 pub struct RemoveHashForLikerInput {
     pub base_liker: AgentPubKey,
     pub target_hash: AnyLinkableHash,
+    /// A lick when None
+    pub reaction: Option<String>,
 }
 #[hdk_extern]
 pub fn remove_hash_for_liker(input: RemoveHashForLikerInput) -> ExternResult<()> {
+    let reaction = input.reaction.unwrap_or(LICK_REACTION.into());
     let links = get_links(input.base_liker.clone(), LinkTypes::LikerToHashes, None)?;
 
     for link in links {
-        if link.target.clone().eq(&input.target_hash) {
+        if link.target.clone().eq(&input.target_hash) && is_link_reaction(&link, &reaction) {
             delete_link(link.create_link_hash)?;
         }
     }
 
-    let links = get_links(input.target_hash.clone(), LinkTypes::HashToLikers, None)?;
+    let links = get_reaction_links_for_hash(input.target_hash.clone(), Some(&reaction))?;
 
     for link in links {
         let entry_hash =
             EntryHash::try_from(link.target.clone()).map_err(|err| wasm_error!(err))?;
         if AgentPubKey::from(entry_hash).eq(&input.base_liker) {
-            delete_link(link.create_link_hash)?;
+            let delete_hash = delete_link(link.create_link_hash)?;
+            if reaction == LICK_REACTION {
+                notify_lick(
+                    NotificationType::MyMewUnlicked,
+                    input.target_hash.clone(),
+                    delete_hash,
+                )?;
+            }
         }
     }
 
     Ok(())
 }
 
+/// Add a lick or unlick to the mew author's notifications, and push it to them
+fn notify_lick(
+    notification_type: NotificationType,
+    hash: AnyLinkableHash,
+    action_hash: ActionHash,
+) -> ExternResult<()> {
+    // Only licks of mews notify their author
+    let mew_hash = match ActionHash::try_from(hash) {
+        Ok(mew_hash) => mew_hash,
+        Err(_) => return Ok(()),
+    };
+
+    call_local_zome::<(), AddNotificationInput>(
+        "mews",
+        "add_notification",
+        AddNotificationInput {
+            notification_type: notification_type.clone(),
+            recipient: None,
+            feed_mew_hash: Some(mew_hash.clone()),
+            action_hash,
+            yarn_response_hash: None,
+        },
+    )?;
+
+    push_notification(SendNotificationSignalInput {
+        notification_type,
+        recipient: None,
+        feed_mew_hash: Some(mew_hash),
+    });
+
+    Ok(())
+}
+
 #[hdk_extern]
 pub fn like(hash: AnyLinkableHash) -> ExternResult<()> {
+    react(ReactInput {
+        hash,
+        reaction: LICK_REACTION.into(),
+    })
+}
+
+#[hdk_extern]
+pub fn unlike(hash: AnyLinkableHash) -> ExternResult<()> {
+    unreact(ReactInput {
+        hash,
+        reaction: LICK_REACTION.into(),
+    })
+}
+
+#[hdk_extern]
+pub fn get_my_liked_hashes(_: ()) -> ExternResult<Vec<AnyLinkableHash>> {
+    get_hashes_for_liker(agent_info()?.agent_initial_pubkey)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReactInput {
+    pub hash: AnyLinkableHash,
+    pub reaction: String,
+}
+#[hdk_extern]
+pub fn react(input: ReactInput) -> ExternResult<()> {
+    // Reacting twice is a no-op, as validation rejects a second active reaction of the same kind
+    if get_my_reactions_for_hash(input.hash.clone())?.contains(&input.reaction) {
+        return Ok(());
+    }
+
     add_hash_for_liker(AddHashForLikerInput {
         base_liker: agent_info()?.agent_initial_pubkey,
-        target_hash: hash,
+        target_hash: input.hash,
+        reaction: Some(input.reaction),
     })
 }
 
 #[hdk_extern]
-pub fn unlike(hash: AnyLinkableHash) -> ExternResult<()> {
+pub fn unreact(input: ReactInput) -> ExternResult<()> {
     remove_hash_for_liker(RemoveHashForLikerInput {
         base_liker: agent_info()?.agent_initial_pubkey,
-        target_hash: hash,
+        target_hash: input.hash,
+        reaction: Some(input.reaction),
     })
 }
 
 #[hdk_extern]
-pub fn get_my_liked_hashes(_: ()) -> ExternResult<Vec<AnyLinkableHash>> {
-    get_hashes_for_liker(agent_info()?.agent_initial_pubkey)
+pub fn count_reactions_for_hash(hash: AnyLinkableHash) -> ExternResult<BTreeMap<String, usize>> {
+    Ok(get_reaction_summary_for_hash(hash)?.counts)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetReactorsForHashInput {
+    pub hash: AnyLinkableHash,
+    /// Agents with any reaction when None
+    pub reaction: Option<String>,
+}
+#[hdk_extern]
+pub fn get_reactors_for_hash(input: GetReactorsForHashInput) -> ExternResult<Vec<AgentPubKey>> {
+    let links = get_reaction_links_for_hash(input.hash, input.reaction.as_deref())?;
+
+    let mut agents: Vec<AgentPubKey> = vec![];
+    for agent in links
+        .into_iter()
+        .filter_map(|link| EntryHash::try_from(link.target).ok())
+        .map(AgentPubKey::from)
+    {
+        if !agents.contains(&agent) {
+            agents.push(agent);
+        }
+    }
+
+    Ok(agents)
+}
+
+#[hdk_extern]
+pub fn get_my_reactions_for_hash(hash: AnyLinkableHash) -> ExternResult<Vec<String>> {
+    Ok(get_reaction_summary_for_hash(hash)?.my_reactions)
+}
+
+/// Counts by reaction and my own reactions, from a single link query
+#[hdk_extern]
+pub fn get_reaction_summary_for_hash(hash: AnyLinkableHash) -> ExternResult<ReactionSummary> {
+    let me = AnyLinkableHash::from(agent_info()?.agent_initial_pubkey);
+
+    Ok(make_reaction_summary(
+        get_reaction_links_for_hash(hash, None)?,
+        &me,
+    ))
+}
+
+/// Reaction summaries of many hashes, in the same order, from a single batch of link queries
+#[hdk_extern]
+pub fn get_reaction_summaries_for_hashes(
+    hashes: Vec<AnyLinkableHash>,
+) -> ExternResult<Vec<ReactionSummary>> {
+    let me = AnyLinkableHash::from(agent_info()?.agent_initial_pubkey);
+    let link_type = LinkTypes::HashToLikers.try_into_filter()?;
+    let inputs: Vec<GetLinksInput> = hashes
+        .into_iter()
+        .map(|hash| GetLinksInput::new(hash, link_type.clone(), None))
+        .collect();
+
+    let links_by_hash = HDK.with(|hdk| hdk.borrow().get_links(inputs))?;
+
+    Ok(links_by_hash
+        .into_iter()
+        .map(|links| make_reaction_summary(links, &me))
+        .collect())
+}
+
+/// The reactions this DNA accepts
+#[hdk_extern]
+pub fn get_allowed_reactions_for_dna(_: ()) -> ExternResult<Vec<String>> {
+    get_allowed_reactions()
+}
+
+/// HashToLikers as resolved in this DNA, for zomes that can't depend on likes_integrity
+#[hdk_extern]
+pub fn get_hash_to_likers_link_type(_: ()) -> ExternResult<ResolvedLinkType> {
+    resolve_link_type("likes_integrity", LinkTypes::HashToLikers)
+}
+
+/// HashToLikers links of one reaction, or of any reaction when None
+fn get_reaction_links_for_hash(
+    hash: AnyLinkableHash,
+    reaction: Option<&str>,
+) -> ExternResult<Vec<Link>> {
+    let links = get_links(hash, LinkTypes::HashToLikers, None)?;
+
+    Ok(match reaction {
+        Some(reaction) => links
+            .into_iter()
+            .filter(|link| is_link_reaction(link, reaction))
+            .collect(),
+        None => links,
+    })
+}
+
+fn make_reaction_summary(links: Vec<Link>, me: &AnyLinkableHash) -> ReactionSummary {
+    let mut summary = ReactionSummary::default();
+    let mut seen: BTreeMap<String, Vec<AnyLinkableHash>> = BTreeMap::new();
+
+    for link in links {
+        let reaction = match ReactionTag::try_from(link.tag) {
+            Ok(reaction_tag) => reaction_tag.reaction,
+            Err(_) => continue,
+        };
+
+        // An agent's reaction counts once, even if several links for it are visible
+        let reactors = seen.entry(reaction.clone()).or_default();
+        if reactors.contains(&link.target) {
+            continue;
+        }
+        reactors.push(link.target.clone());
+
+        if link.target == *me {
+            summary.my_reactions.push(reaction.clone());
+        }
+        *summary.counts.entry(reaction).or_insert(0) += 1;
+    }
+
+    summary
+}
+
+fn is_link_reaction(link: &Link, reaction: &str) -> bool {
+    ReactionTag::try_from(link.tag.clone())
+        .map(|reaction_tag| reaction_tag.reaction == reaction)
+        .unwrap_or(false)
+}
+
+fn make_reaction_tag(reaction: Option<String>) -> ExternResult<LinkTag> {
+    let tag: SerializedBytes = match reaction {
+        Some(reaction) => ReactionTag { reaction },
+        None => ReactionTag::default(),
+    }
+    .try_into()
+    .map_err(|_| {
+        wasm_error!(WasmErrorInner::Guest(
+            "Failed to serialize ReactionTag".into()
+        ))
+    })?;
+
+    Ok(LinkTag::from(tag.bytes().clone()))
 }
@@ -1,12 +1,17 @@
-use crate::mew_with_context::get_batch_mews_with_context;
-use hc_link_pagination::{paginate_by_hash, HashPagination};
+use crate::mew_with_context::{get_batch_mews_with_options, get_page_mews_with_context};
+use crate::time_bucket_to_mews::get_mew_links_for_time_buckets;
+use hc_link_pagination::{
+    paginate_by_cursor, paginate_by_hash, CursorPagination, HashPagination, Page,
+};
 use hdk::prelude::*;
 use mews_integrity::*;
+use std::collections::HashSet;
 
 #[derive(Serialize, Deserialize, SerializedBytes, Clone, Debug)]
 pub struct GetAgentMewsInput {
     pub agent: AgentPubKey,
     pub page: Option<HashPagination>,
+    pub options: Option<FeedMewOptions>,
 }
 #[hdk_extern]
 pub fn get_agent_mews(input: GetAgentMewsInput) -> ExternResult<Vec<Record>> {
@@ -22,9 +27,31 @@ pub fn get_agent_mews(input: GetAgentMewsInput) -> ExternResult<Vec<Record>> {
 
 #[hdk_extern]
 pub fn get_agent_mews_with_context(input: GetAgentMewsInput) -> ExternResult<Vec<FeedMew>> {
+    let options = input.options.clone().unwrap_or_default();
     let hashes = get_agent_mew_hashes(input)?;
 
-    get_batch_mews_with_context(hashes)
+    get_batch_mews_with_options(hashes, &options)
+}
+
+#[derive(Serialize, Deserialize, SerializedBytes, Clone, Debug)]
+pub struct GetAgentMewsPageInput {
+    pub agent: AgentPubKey,
+    pub page: Option<CursorPagination>,
+    pub options: Option<FeedMewOptions>,
+}
+#[hdk_extern]
+pub fn get_agent_mews_page_with_context(
+    input: GetAgentMewsPageInput,
+) -> ExternResult<Page<FeedMew>> {
+    // Viewing an agent's mews is an explicit choice, so none are hidden
+    let links = get_mew_links_for_time_buckets(
+        make_agent_mews_time_bucket_root(input.agent),
+        &input.page,
+        &HashSet::new(),
+    )?;
+    let links_page = paginate_by_cursor(links, input.page)?;
+
+    get_page_mews_with_context(links_page, &input.options.unwrap_or_default())
 }
 
 fn get_agent_mew_hashes(input: GetAgentMewsInput) -> ExternResult<Vec<ActionHash>> {
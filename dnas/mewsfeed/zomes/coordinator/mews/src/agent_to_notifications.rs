@@ -1,168 +1,47 @@
-use crate::agent_mews::get_agent_mews;
-use crate::agent_mews::GetAgentMewsInput;
-use crate::mew_to_responses::{get_responses_for_mew, GetResponsesForMewInput};
-use crate::mew_with_context::get_mew_with_context;
+use crate::hidden_agents::get_my_hidden_agents;
+use crate::mew_with_context::get_batch_mews_with_options;
+use crate::notification_preferences::{get_my_notification_preferences, is_notification_muted};
+use crate::notifications_read::get_my_last_read_timestamp;
 use hc_call_utils::call_local_zome;
-use hc_link_pagination::{paginate_by_timestamp, TimestampPagination};
+use hc_link_pagination::{paginate_by_timestamp, TimestampPagination, Timestamped};
 use hdk::prelude::*;
-use mews_types::{Mew, MewType, Notification, NotificationType, Profile};
+use mews_integrity::*;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+/// A notification before its agent profile and mew are fetched
+#[derive(Debug, Clone)]
+struct NotificationEvent {
+    notification_type: NotificationType,
+    timestamp: Timestamp,
+    agent: AgentPubKey,
+    feed_mew_hash: Option<ActionHash>,
+}
+
+impl Timestamped for NotificationEvent {
+    fn timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+}
 
 #[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
 pub struct GetNotificationsForAgentInput {
     agent: AgentPubKey,
     page: Option<TimestampPagination>,
+    /// Only notifications newer than my NotificationsRead, so only available for my own agent
+    unread_only: Option<bool>,
 }
 #[hdk_extern]
 pub fn get_notifications_for_agent(
     input: GetNotificationsForAgentInput,
 ) -> ExternResult<Vec<Notification>> {
-    let agent_mews = get_agent_mews(GetAgentMewsInput {
-        agent: input.agent.clone(),
-        page: None,
-    })?;
+    let read_cursor = get_read_cursor(&input.agent, input.unread_only)?;
+    let events = get_notification_events_for_agent(input.agent, read_cursor)?;
 
-    let agent_link_details = get_link_details(
-        input.agent.clone(),
-        LinkTypeFilter::Types(vec![
-            // Mentions of agent (MentionToMews)
-            (ZomeIndex(1), vec![LinkType(6)]),
-            // Follows of agent (CreatorToFollowers)
-            (ZomeIndex(2), vec![LinkType(1)]),
-        ]),
-        None,
-    )?;
+    // Only the requested page is fetched with profiles and mews
+    let events_page = paginate_by_timestamp(events, input.page)?;
 
-    let mut all_link_details = agent_mews
-        .iter()
-        .map(|mew| {
-            get_link_details(
-                mew.action_hashed().hash.clone(),
-                LinkTypeFilter::Types(vec![
-                    // MewToResponses
-                    (ZomeIndex(1), vec![LinkType(5)]),
-                    // HashToLikers
-                    (ZomeIndex(3), vec![LinkType(1)]),
-                    // HashToPinners
-                    (ZomeIndex(4), vec![LinkType(1)]),
-                ]),
-                None,
-            )
-        })
-        .collect::<ExternResult<Vec<LinkDetails>>>()?;
-
-    if !agent_link_details.clone().into_inner().is_empty() {
-        all_link_details.push(agent_link_details);
-    }
-
-    let mut notifications: Vec<Notification> = all_link_details
-        .iter()
-        .map(|link_details| -> ExternResult<Vec<Vec<Notification>>> {
-            link_details.clone().into_inner()
-                .iter()
-                .map(|(create_action_hashed, delete_actions_hashed)|  -> ExternResult<Vec<Notification>> {
-                    let create = match create_action_hashed.action() {
-                        Action::CreateLink(a) => Ok(a.clone()),
-                        _ => Err(wasm_error!(WasmErrorInner::Guest("Expected first element of LinkDetails to be CreateLink".into())))
-                    }?;
-                    if create.author == input.agent {
-                        return Ok(vec!());
-                    }
-
-                    let deletes = delete_actions_hashed
-                        .iter()
-                        .filter(|action_hashed| *action_hashed.action().author() != input.agent)
-                        .map(|action_hashed| -> ExternResult<DeleteLink> {
-                            match action_hashed.action() {
-                                Action::DeleteLink(a) => Ok(a.clone()),
-                                _ => Err(wasm_error!(WasmErrorInner::Guest("Expected first element of LinkDetails to be CreateLink".into())))
-                            }
-                        })
-                        .collect::<ExternResult<Vec<DeleteLink>>>()?;
-
-                    make_notifications(create, deletes)
-                })
-                .collect::<ExternResult<Vec<Vec<Notification>>>>()
-        })
-        .collect::<ExternResult<Vec<Vec<Vec<Notification>>>>>()?
-        .iter()
-        .flatten()
-        .flatten()
-        .cloned()
-        .collect();
-
-    // Responses to Mews I have responded to
-    let mut mew_hashes_i_responded_to: Vec<(Record, ActionHash)> = agent_mews
-        .iter()
-        // Filter only mew types that are responses
-        .filter_map(
-            |response_record| match response_record.entry().to_app_option::<Mew>().ok() {
-                Some(Some(mew)) => match mew.mew_type {
-                    MewType::Reply(original_ah)
-                    | MewType::Quote(original_ah)
-                    | MewType::Mewmew(original_ah) => Some((response_record.clone(), original_ah)),
-                    _ => None,
-                },
-                _ => None,
-            },
-        )
-        // Exclude responses to agent's mews to avoid duplicate notifications for both "responded to your mew" and "responded to a yarn you participated in"
-        .filter(|(_, original_ah)| {
-            agent_mews
-                .iter()
-                .filter(|authored_record| {
-                    authored_record.action_hashed().hash == original_ah.clone()
-                })
-                .count()
-                == 0
-        })
-        .collect();
-    mew_hashes_i_responded_to
-        .sort_by_key(|(response_record, _)| response_record.action().timestamp());
-    mew_hashes_i_responded_to.dedup_by_key(|(_, original_ah)| original_ah.clone());
-
-    let mews_responding_to_mews_i_responded_to: Vec<(Record, Vec<Record>)> =
-        mew_hashes_i_responded_to
-            .iter()
-            .map(|(my_response, original_ah)| {
-                // Still have to use a get_links here because we cannot filter count_links by excluding an author
-                let responses_result = get_responses_for_mew(GetResponsesForMewInput {
-                    original_mew_hash: original_ah.clone(),
-                    response_type: None,
-                    page: None,
-                });
-
-                match responses_result {
-                    Ok(all_responses) => Ok((my_response.clone(), all_responses)),
-                    Err(e) => Err(e),
-                }
-            })
-            .collect::<ExternResult<Vec<(Record, Vec<Record>)>>>()?;
-
-    let mews_responding_to_mews_i_responded_to = mews_responding_to_mews_i_responded_to
-        .iter()
-        .flat_map(|(my_response, all_responses)| -> Vec<Record> {
-            all_responses
-                .iter()
-                .filter(|other_response| {
-                    other_response.action().author().clone() != input.agent.clone()
-                        && other_response.action().timestamp() >= my_response.action().timestamp()
-                })
-                .cloned()
-                .collect()
-        })
-        .collect();
-
-    let mut n = make_notifications_for_records(
-        mews_responding_to_mews_i_responded_to,
-        NotificationType::FollowedYarnResponded,
-        true,
-    )?;
-    notifications.append(&mut n);
-
-    // All of this combined into one list sorted by timestamp descending
-    let notifications_page = paginate_by_timestamp(notifications.clone(), input.page)?;
-
-    Ok(notifications_page)
+    make_notifications(events_page)
 }
 
 #[hdk_extern]
@@ -170,367 +49,450 @@ pub fn get_my_notifications(page: Option<TimestampPagination>) -> ExternResult<V
     get_notifications_for_agent(GetNotificationsForAgentInput {
         agent: agent_info()?.agent_initial_pubkey,
         page,
+        unread_only: None,
     })
 }
 
 #[hdk_extern]
-pub fn count_notifications_for_agent(agent: AgentPubKey) -> ExternResult<usize> {
-    let agent_mews = get_agent_mews(GetAgentMewsInput {
-        agent: agent.clone(),
-        page: None,
-    })?;
+pub fn get_my_unread_notifications(
+    page: Option<TimestampPagination>,
+) -> ExternResult<Vec<Notification>> {
+    get_notifications_for_agent(GetNotificationsForAgentInput {
+        agent: agent_info()?.agent_initial_pubkey,
+        page,
+        unread_only: Some(true),
+    })
+}
 
-    let agent_link_details = get_link_details(
-        agent.clone(),
-        LinkTypeFilter::Types(vec![
-            // Mentions of agent (MentionToMews)
-            (ZomeIndex(1), vec![LinkType(6)]),
-            // Follows of agent (CreatorToFollowers)
-            (ZomeIndex(2), vec![LinkType(1)]),
-        ]),
-        None,
-    )?;
+#[hdk_extern]
+pub fn count_notifications_for_agent(agent: AgentPubKey) -> ExternResult<usize> {
+    Ok(get_notification_events_for_agent(agent, None)?.len())
+}
 
-    let mut all_link_details = agent_mews
-        .iter()
-        .map(|mew| {
-            get_link_details(
-                mew.action_hashed().hash.clone(),
-                LinkTypeFilter::Types(vec![
-                    // MewToResponses
-                    (ZomeIndex(1), vec![LinkType(5)]),
-                    // HashToLikers
-                    (ZomeIndex(3), vec![LinkType(1)]),
-                    // HashToPinners
-                    (ZomeIndex(4), vec![LinkType(1)]),
-                ]),
-                None,
-            )
-        })
-        .collect::<ExternResult<Vec<LinkDetails>>>()?;
+#[hdk_extern]
+pub fn count_my_notifications(_: ()) -> ExternResult<usize> {
+    count_notifications_for_agent(agent_info()?.agent_initial_pubkey)
+}
 
-    if !agent_link_details.clone().into_inner().is_empty() {
-        all_link_details.push(agent_link_details);
-    }
+/// Notifications within this window of the latest one in their group are grouped together
+const DEFAULT_GROUP_WINDOW_SECONDS: u64 = 24 * 60 * 60;
 
-    let notifications_count: usize = all_link_details
-        .iter()
-        .map(|link_details| -> ExternResult<Vec<usize>> {
-            link_details
-                .clone()
-                .into_inner()
-                .iter()
-                .map(
-                    |(create_action_hashed, delete_actions_hashed)| -> ExternResult<usize> {
-                        let create = match create_action_hashed.action() {
-                            Action::CreateLink(a) => Ok(a.clone()),
-                            _ => Err(wasm_error!(WasmErrorInner::Guest(
-                                "Expected first element of LinkDetails to be CreateLink".into()
-                            ))),
-                        }?;
-                        if create.author == agent {
-                            return Ok(0);
-                        }
-
-                        let deletes = delete_actions_hashed
-                            .iter()
-                            .filter(|action_hashed| *action_hashed.action().author() != agent)
-                            .map(|action_hashed| -> ExternResult<DeleteLink> {
-                                match action_hashed.action() {
-                                    Action::DeleteLink(a) => Ok(a.clone()),
-                                    _ => Err(wasm_error!(WasmErrorInner::Guest(
-                                        "Expected first element of LinkDetails to be CreateLink"
-                                            .into()
-                                    ))),
-                                }
-                            })
-                            .collect::<ExternResult<Vec<DeleteLink>>>()?;
-
-                        count_notifications(create, deletes)
-                    },
-                )
-                .collect::<ExternResult<Vec<usize>>>()
-        })
-        .collect::<ExternResult<Vec<Vec<usize>>>>()?
-        .iter()
-        .flatten()
-        .cloned()
-        .sum();
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+pub struct GetGroupedNotificationsForAgentInput {
+    agent: AgentPubKey,
+    page: Option<TimestampPagination>,
+    unread_only: Option<bool>,
+    group_window_seconds: Option<u64>,
+}
+#[hdk_extern]
+pub fn get_grouped_notifications_for_agent(
+    input: GetGroupedNotificationsForAgentInput,
+) -> ExternResult<Vec<GroupedNotification>> {
+    let read_cursor = get_read_cursor(&input.agent, input.unread_only)?;
+    let events = get_notification_events_for_agent(input.agent, read_cursor)?;
+
+    let groups = group_notification_events(
+        events,
+        input
+            .group_window_seconds
+            .unwrap_or(DEFAULT_GROUP_WINDOW_SECONDS),
+    );
+    let groups_page = paginate_by_timestamp(groups, input.page)?;
+
+    make_grouped_notifications(groups_page)
+}
 
-    // Responses to Mews I have responded to
-    let mut mew_hashes_i_responded_to: Vec<(Record, ActionHash)> = agent_mews
-        .iter()
-        .filter_map(
-            |response_record| match response_record.entry().to_app_option::<Mew>().ok() {
-                Some(Some(mew)) => match mew.mew_type {
-                    MewType::Reply(original_ah)
-                    | MewType::Quote(original_ah)
-                    | MewType::Mewmew(original_ah) => Some((response_record.clone(), original_ah)),
-                    _ => None,
-                },
-                _ => None,
-            },
-        )
-        .filter(|(_, original_ah)| {
-            agent_mews
-                .iter()
-                .filter(|authored_record| {
-                    authored_record.action_hashed().hash == original_ah.clone()
-                })
-                .count()
-                == 0
-        })
-        .collect();
-    mew_hashes_i_responded_to
-        .sort_by_key(|(response_record, _)| response_record.action().timestamp());
-    mew_hashes_i_responded_to.dedup_by_key(|(_, original_ah)| original_ah.clone());
+#[hdk_extern]
+pub fn get_my_grouped_notifications(
+    page: Option<TimestampPagination>,
+) -> ExternResult<Vec<GroupedNotification>> {
+    get_grouped_notifications_for_agent(GetGroupedNotificationsForAgentInput {
+        agent: agent_info()?.agent_initial_pubkey,
+        page,
+        unread_only: None,
+        group_window_seconds: None,
+    })
+}
 
-    let mews_responding_to_mews_i_responded_to: Vec<(Record, Vec<Record>)> =
-        mew_hashes_i_responded_to
-            .iter()
-            .map(|(my_response, original_ah)| {
-                // Still have to use a get_links here because we cannot filter count_links by excluding an author
-                let responses_result = get_responses_for_mew(GetResponsesForMewInput {
-                    original_mew_hash: original_ah.clone(),
-                    response_type: None,
-                    page: None,
-                });
+/// Count my notifications newer than my NotificationsRead, without fetching their profiles or mews
+#[hdk_extern]
+pub fn count_unread_notifications(_: ()) -> ExternResult<usize> {
+    let read_cursor = get_my_last_read_timestamp()?;
+    let events =
+        get_notification_events_for_agent(agent_info()?.agent_initial_pubkey, read_cursor)?;
 
-                match responses_result {
-                    Ok(all_responses) => Ok((my_response.clone(), all_responses)),
-                    Err(e) => Err(e),
-                }
-            })
-            .collect::<ExternResult<Vec<(Record, Vec<Record>)>>>()?;
+    Ok(events.len())
+}
 
-    let mews_responding_to_mews_i_responded_to_count = mews_responding_to_mews_i_responded_to
-        .iter()
-        .flat_map(|(my_response, all_responses)| -> Vec<Record> {
-            all_responses
-                .iter()
-                .filter(|other_response| {
-                    other_response.action().author().clone() != agent.clone()
-                        && other_response.action().timestamp() >= my_response.action().timestamp()
-                })
-                .cloned()
-                .collect()
-        })
-        .count();
+/// The timestamp that notifications must be newer than, which is my NotificationsRead when only
+/// unread ones are asked for
+fn get_read_cursor(
+    agent: &AgentPubKey,
+    unread_only: Option<bool>,
+) -> ExternResult<Option<Timestamp>> {
+    if !unread_only.unwrap_or(false) {
+        return Ok(None);
+    }
+    if *agent != agent_info()?.agent_initial_pubkey {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Unread notifications are only available for your own agent"
+        ))));
+    }
 
-    Ok(notifications_count + mews_responding_to_mews_i_responded_to_count)
+    get_my_last_read_timestamp()
 }
 
+/// Add a notification about an action of mine to the notifications of the agent it concerns
+///
+/// Notifications about something other than a mew, such as a like of a profile, are not added.
 #[hdk_extern]
-pub fn count_my_notifications(_: ()) -> ExternResult<usize> {
-    count_notifications_for_agent(agent_info()?.agent_initial_pubkey)
-}
+pub fn add_notification(input: AddNotificationInput) -> ExternResult<()> {
+    let feed_mew = match &input.feed_mew_hash {
+        Some(feed_mew_hash) => {
+            let record = get(feed_mew_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
+                WasmErrorInner::Guest(String::from("Could not find the notified mew"))
+            ))?;
+            let mew: Mew = match record.entry().to_app_option() {
+                Ok(Some(mew)) => mew,
+                _ => return Ok(()),
+            };
+            Some((record.action().author().clone(), mew))
+        }
+        None => None,
+    };
+    let recipient = match (input.recipient, &feed_mew) {
+        (Some(recipient), _) => recipient,
+        (None, Some((author, _))) => author.clone(),
+        (None, None) => {
+            return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+                "Notification needs a recipient or a feed mew"
+            ))))
+        }
+    };
+    if recipient == agent_info()?.agent_initial_pubkey {
+        return Ok(());
+    }
 
-fn count_notifications(create: CreateLink, deletes: Vec<DeleteLink>) -> ExternResult<usize> {
-    match (create.zome_index, create.link_type) {
-        // MentionToMews
-        (ZomeIndex(1), LinkType(6)) => Ok(1),
+    add_notification_for_agent(
+        recipient,
+        NotificationTag {
+            notification_type: input.notification_type,
+            action_hash: input.action_hash,
+            feed_mew_hash: input.feed_mew_hash,
+            original_mew_hash: feed_mew.and_then(|(_, mew)| get_original_mew_hash(&mew)),
+            yarn_response_hash: input.yarn_response_hash,
+        },
+    )
+}
 
-        // CreatorToFollowers
-        (ZomeIndex(2), LinkType(1)) => Ok(1 + deletes.len()),
+/// Add a notification to the recipient's notification time buckets, so that reading their unread
+/// notifications only walks the buckets after their read cursor
+fn add_notification_for_agent(
+    recipient: AgentPubKey,
+    notification_tag: NotificationTag,
+) -> ExternResult<()> {
+    // The notified action was just committed by me, so it is read back from my own chain
+    let notified_record = query(ChainQueryFilter::new().sequence_range(
+        ChainQueryFilterRange::ActionHashTerminated(notification_tag.action_hash.clone(), 0),
+    ))?
+    .pop()
+    .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+        "Could not find the notified action"
+    ))))?;
+
+    let path = make_time_bucket_path(
+        make_agent_notifications_time_bucket_root(recipient.clone()),
+        notified_record.action().timestamp(),
+    )
+    .typed(LinkTypes::TimeBucket)?;
+    path.ensure()?;
+
+    let tag: SerializedBytes = notification_tag.try_into().map_err(|_| {
+        wasm_error!(WasmErrorInner::Guest(
+            "Failed to serialize NotificationTag".into()
+        ))
+    })?;
+    create_link(
+        path.path_entry_hash()?,
+        recipient,
+        LinkTypes::TimeBucketToNotifications,
+        LinkTag::from(tag.bytes().clone()),
+    )?;
 
-        // MewToResponses
-        (ZomeIndex(1), LinkType(5)) => Ok(1),
+    Ok(())
+}
 
-        // HashToLikers
-        (ZomeIndex(3), LinkType(1)) => Ok(1 + deletes.len()),
+/// Notification events for the agent newer than the read cursor, leaving out those from agents I
+/// have hidden and those muted by my NotificationPreferences when the agent is me
+///
+/// Only the agent's notification time buckets from the read cursor on are walked, so reading
+/// unread notifications doesn't depend on the agent's whole history.
+fn get_notification_events_for_agent(
+    agent: AgentPubKey,
+    read_cursor: Option<Timestamp>,
+) -> ExternResult<Vec<NotificationEvent>> {
+    let (preferences, hidden_agents) = match agent == agent_info()?.agent_initial_pubkey {
+        true => (
+            get_my_notification_preferences(())?,
+            get_my_hidden_agents()?,
+        ),
+        false => (NotificationPreferences::default(), HashSet::new()),
+    };
+
+    let mut links: Vec<Link> = vec![];
+    collect_notification_links(
+        make_agent_notifications_time_bucket_root(agent.clone()).typed(LinkTypes::TimeBucket)?,
+        vec![],
+        read_cursor,
+        &mut links,
+    )?;
 
-        // HashToPinners
-        (ZomeIndex(4), LinkType(1)) => Ok(1 + deletes.len()),
+    let mut events: Vec<NotificationEvent> = vec![];
+    for link in links {
+        let notification_tag = match NotificationTag::try_from(link.tag) {
+            Ok(notification_tag) => notification_tag,
+            Err(_) => continue,
+        };
+        let is_muted_yarn = notification_tag.notification_type
+            == NotificationType::FollowedYarnResponded
+            && notification_tag
+                .original_mew_hash
+                .map_or(false, |hash| preferences.muted_yarns.contains(&hash));
+        if link.author == agent
+            || is_muted_yarn
+            || hidden_agents.contains(&link.author)
+            || is_notification_muted(
+                &preferences,
+                &notification_tag.notification_type,
+                &link.author,
+            )
+        {
+            continue;
+        }
 
-        (_, _) => Err(wasm_error!(WasmErrorInner::Guest(
-            "Unexpected link type".into()
-        ))),
+        events.push(NotificationEvent {
+            notification_type: notification_tag.notification_type,
+            timestamp: link.timestamp,
+            agent: link.author,
+            feed_mew_hash: notification_tag.feed_mew_hash,
+        });
     }
-}
 
-fn make_notifications(
-    create: CreateLink,
-    deletes: Vec<DeleteLink>,
-) -> ExternResult<Vec<Notification>> {
-    match (create.zome_index, create.link_type) {
-        // MentionToMews
-        (ZomeIndex(1), LinkType(6)) => {
-            let feed_mew_hash = Some(
-                ActionHash::try_from(create.target_address.clone())
-                    .map_err(|err| wasm_error!(err))?,
-            );
-
-            make_notifications_for_createlinks(
-                vec![create],
-                NotificationType::MyAgentMentioned,
-                feed_mew_hash,
-            )
-        }
+    Ok(events)
+}
 
-        // CreatorToFollowers
-        (ZomeIndex(2), LinkType(1)) => {
-            let mut all_notifications = make_notifications_for_createlinks(
-                vec![create],
-                NotificationType::MyAgentFollowed,
-                None,
-            )?;
-
-            let mut delete_notifications = make_notifications_for_deletelinks(
-                deletes,
-                NotificationType::MyAgentUnfollowed,
-                None,
-            )?;
-            all_notifications.append(&mut delete_notifications);
-
-            Ok(all_notifications)
-        }
+/// Walk notification time buckets from oldest to newest, skipping the buckets that only hold
+/// notifications at or before the read cursor
+fn collect_notification_links(
+    path: TypedPath,
+    bucket: Vec<u32>,
+    read_cursor: Option<Timestamp>,
+    links: &mut Vec<Link>,
+) -> ExternResult<()> {
+    if bucket.len() == TIME_BUCKET_DEPTH {
+        let bucket_links = get_links(
+            path.path_entry_hash()?,
+            LinkTypes::TimeBucketToNotifications,
+            None,
+        )?;
+        links.extend(bucket_links.into_iter().filter(|link| match read_cursor {
+            Some(read_cursor) => link.timestamp > read_cursor,
+            None => true,
+        }));
+
+        return Ok(());
+    }
 
-        // MewToResponses
-        (ZomeIndex(1), LinkType(5)) => {
-            let feed_mew_hash = Some(
-                ActionHash::try_from(create.target_address.clone())
-                    .map_err(|err| wasm_error!(err))?,
-            );
-
-            make_notifications_for_createlinks(
-                vec![create],
-                NotificationType::MyMewResponded,
-                feed_mew_hash,
-            )
-        }
+    let cursor_bucket = read_cursor.map(time_bucket_for_timestamp);
+    let mut children: Vec<(u32, TypedPath)> = path
+        .children_paths()?
+        .into_iter()
+        .filter_map(|child| {
+            let value = child
+                .leaf()
+                .and_then(|component| String::try_from(component).ok())
+                .and_then(|component| component.parse::<u32>().ok())?;
+
+            Some((value, child))
+        })
+        .collect();
+    children.sort_by_key(|(value, _)| *value);
 
-        // HashToLikers
-        (ZomeIndex(3), LinkType(1)) => {
-            let feed_mew_hash = Some(
-                ActionHash::try_from(create.base_address.clone())
-                    .map_err(|err| wasm_error!(err))?,
-            );
-
-            let mut all_notifications = make_notifications_for_createlinks(
-                vec![create],
-                NotificationType::MyMewLicked,
-                feed_mew_hash.clone(),
-            )?;
-
-            let mut delete_notifications = make_notifications_for_deletelinks(
-                deletes,
-                NotificationType::MyMewUnlicked,
-                feed_mew_hash,
-            )?;
-            all_notifications.append(&mut delete_notifications);
-
-            Ok(all_notifications)
-        }
+    for (value, child) in children {
+        let mut child_bucket = bucket.clone();
+        child_bucket.push(value);
 
-        // HashToPinners
-        (ZomeIndex(4), LinkType(1)) => {
-            let feed_mew_hash = Some(
-                ActionHash::try_from(create.base_address.clone())
-                    .map_err(|err| wasm_error!(err))?,
-            );
-
-            let mut all_notifications = make_notifications_for_createlinks(
-                vec![create],
-                NotificationType::MyMewPinned,
-                feed_mew_hash.clone(),
-            )?;
-
-            let mut delete_notifications = make_notifications_for_deletelinks(
-                deletes,
-                NotificationType::MyMewUnpinned,
-                feed_mew_hash,
-            )?;
-            all_notifications.append(&mut delete_notifications);
-
-            Ok(all_notifications)
+        if let Some(cursor_bucket) = &cursor_bucket {
+            if child_bucket[..] < cursor_bucket[..child_bucket.len()] {
+                continue;
+            }
         }
 
-        (_, _) => Err(wasm_error!(WasmErrorInner::Guest(
-            "Unexpected link type".into()
-        ))),
+        collect_notification_links(child, child_bucket, read_cursor, links)?;
     }
+
+    Ok(())
 }
 
-fn make_notifications_for_createlinks(
-    create_link_actions: Vec<CreateLink>,
+/// Notification events of the same type about the same mew, before their profile and mew are fetched
+#[derive(Debug, Clone)]
+struct NotificationEventGroup {
     notification_type: NotificationType,
+    agents: Vec<AgentPubKey>,
+    count: usize,
+    latest_timestamp: Timestamp,
     feed_mew_hash: Option<ActionHash>,
-) -> ExternResult<Vec<Notification>> {
-    let notifications: Vec<Notification> = create_link_actions
-        .iter()
-        .map(|create_action| -> ExternResult<Notification> {
-            let agent_profile = get_agent_profile(create_action.author.clone())?;
-            let feed_mew = match feed_mew_hash.clone() {
-                Some(hash) => Some(get_mew_with_context(hash)?),
-                None => None,
-            };
+}
 
-            Ok(Notification {
-                agent: create_action.author.clone(),
-                agent_profile,
-                timestamp: create_action.timestamp,
-                notification_type: notification_type.clone(),
-                feed_mew,
-            })
-        })
-        .collect::<ExternResult<Vec<Notification>>>()?;
+impl Timestamped for NotificationEventGroup {
+    fn timestamp(&self) -> Timestamp {
+        self.latest_timestamp
+    }
+}
+
+/// Group events newest first, starting a new group once an event is older than the window from its group's latest event
+fn group_notification_events(
+    mut events: Vec<NotificationEvent>,
+    group_window_seconds: u64,
+) -> Vec<NotificationEventGroup> {
+    let group_window_micros = i64::try_from(group_window_seconds)
+        .unwrap_or(i64::MAX)
+        .saturating_mul(1_000_000);
+    events.sort_by_key(|event| Reverse(event.timestamp));
+
+    let mut groups: Vec<NotificationEventGroup> = vec![];
+    let mut open_groups: HashMap<(NotificationType, Option<ActionHash>), usize> = HashMap::new();
+    for event in events {
+        let key = (event.notification_type.clone(), event.feed_mew_hash.clone());
+        let open_group = open_groups.get(&key).and_then(|index| {
+            let group = &groups[*index];
+            match group.latest_timestamp.as_micros() - event.timestamp.as_micros()
+                <= group_window_micros
+            {
+                true => Some(*index),
+                false => None,
+            }
+        });
+
+        match open_group {
+            Some(index) => {
+                let group = &mut groups[index];
+                if !group.agents.contains(&event.agent) {
+                    group.agents.push(event.agent);
+                }
+                group.count += 1;
+            }
+            None => {
+                open_groups.insert(key, groups.len());
+                groups.push(NotificationEventGroup {
+                    notification_type: event.notification_type,
+                    agents: vec![event.agent],
+                    count: 1,
+                    latest_timestamp: event.timestamp,
+                    feed_mew_hash: event.feed_mew_hash,
+                });
+            }
+        }
+    }
 
-    Ok(notifications)
+    groups
 }
 
-fn make_notifications_for_deletelinks(
-    delete_link_actions: Vec<DeleteLink>,
-    notification_type: NotificationType,
-    feed_mew_hash: Option<ActionHash>,
-) -> ExternResult<Vec<Notification>> {
-    let notifications: Vec<Notification> = delete_link_actions
-        .iter()
-        .map(|delete_action| {
-            let agent_profile = get_agent_profile(delete_action.author.clone())?;
-            let feed_mew = match feed_mew_hash.clone() {
-                Some(hash) => Some(get_mew_with_context(hash)?),
+/// Fetch the mews of grouped notification events and the profile of each group's latest agent
+fn make_grouped_notifications(
+    groups: Vec<NotificationEventGroup>,
+) -> ExternResult<Vec<GroupedNotification>> {
+    let feed_mews = get_notification_feed_mews(
+        groups
+            .iter()
+            .filter_map(|group| group.feed_mew_hash.as_ref()),
+    )?;
+
+    let mut profiles: HashMap<AgentPubKey, Option<Profile>> = HashMap::new();
+    groups
+        .into_iter()
+        .map(|group| -> ExternResult<GroupedNotification> {
+            let latest_agent_profile = match group.agents.first() {
+                Some(agent) => get_cached_agent_profile(&mut profiles, agent)?,
                 None => None,
             };
 
-            Ok(Notification {
-                agent: delete_action.author.clone(),
-                agent_profile,
-                timestamp: delete_action.timestamp,
-                notification_type: notification_type.clone(),
-                feed_mew,
+            Ok(GroupedNotification {
+                notification_type: group.notification_type,
+                agents: group.agents,
+                latest_agent_profile,
+                count: group.count,
+                latest_timestamp: group.latest_timestamp,
+                feed_mew: group
+                    .feed_mew_hash
+                    .and_then(|hash| feed_mews.get(&hash).cloned()),
             })
         })
-        .collect::<ExternResult<Vec<Notification>>>()?;
-
-    Ok(notifications)
+        .collect()
 }
 
-fn make_notifications_for_records(
-    records: Vec<Record>,
-    notification_type: NotificationType,
-    include_feed_mew: bool,
-) -> ExternResult<Vec<Notification>> {
-    records
-        .iter()
-        .map(|record| -> ExternResult<Notification> {
-            let agent_profile = get_agent_profile(record.action_hashed().author().clone())?;
-            let feed_mew = match include_feed_mew {
-                true => Some(get_mew_with_context(record.action_hashed().hash.clone())?),
-                false => None,
-            };
+/// Fetch the profiles and mews of notification events, each only once
+fn make_notifications(events: Vec<NotificationEvent>) -> ExternResult<Vec<Notification>> {
+    let feed_mews = get_notification_feed_mews(
+        events
+            .iter()
+            .filter_map(|event| event.feed_mew_hash.as_ref()),
+    )?;
+
+    let mut profiles: HashMap<AgentPubKey, Option<Profile>> = HashMap::new();
+    events
+        .into_iter()
+        .map(|event| -> ExternResult<Notification> {
+            let agent_profile = get_cached_agent_profile(&mut profiles, &event.agent)?;
 
             Ok(Notification {
-                agent: record.action_hashed().author().clone(),
+                feed_mew: event
+                    .feed_mew_hash
+                    .and_then(|hash| feed_mews.get(&hash).cloned()),
+                agent: event.agent,
                 agent_profile,
-                timestamp: record.action_hashed().timestamp(),
-                notification_type: notification_type.clone(),
-                feed_mew,
+                timestamp: event.timestamp,
+                notification_type: event.notification_type,
             })
         })
-        .collect::<ExternResult<Vec<Notification>>>()
+        .collect()
+}
+
+fn get_notification_feed_mews<'a>(
+    hashes: impl Iterator<Item = &'a ActionHash>,
+) -> ExternResult<HashMap<ActionHash, FeedMew>> {
+    let mut feed_mew_hashes: Vec<ActionHash> = vec![];
+    for hash in hashes {
+        if !feed_mew_hashes.contains(hash) {
+            feed_mew_hashes.push(hash.clone());
+        }
+    }
+
+    Ok(feed_mew_hashes
+        .iter()
+        .cloned()
+        .zip(get_batch_mews_with_options(
+            feed_mew_hashes.clone(),
+            &FeedMewOptions::default(),
+        )?)
+        .collect())
+}
+
+fn get_cached_agent_profile(
+    profiles: &mut HashMap<AgentPubKey, Option<Profile>>,
+    agent: &AgentPubKey,
+) -> ExternResult<Option<Profile>> {
+    match profiles.get(agent) {
+        Some(profile) => Ok(profile.clone()),
+        None => {
+            let profile = get_agent_profile(agent.clone())?;
+            profiles.insert(agent.clone(), profile.clone());
+            Ok(profile)
+        }
+    }
 }
 
-fn get_agent_profile(agent_pub_key: AgentPubKey) -> ExternResult<Option<Profile>> {
+pub fn get_agent_profile(agent_pub_key: AgentPubKey) -> ExternResult<Option<Profile>> {
     let maybe_agent_profile_record = call_local_zome::<Option<Record>, AgentPubKey>(
         "profiles",
         "get_agent_profile",
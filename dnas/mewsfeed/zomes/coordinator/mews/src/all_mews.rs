@@ -1,9 +1,17 @@
+use crate::hidden_agents::{filter_hidden_links, get_my_hidden_agents};
+use crate::mew_with_context::get_page_mews_with_context;
+use crate::time_bucket_to_mews::get_mew_links_for_time_buckets;
+use hc_link_pagination::{paginate_by_cursor, CursorPagination, Page};
 use hdk::prelude::*;
 use mews_integrity::*;
 
 pub fn get_all_mew_hashes() -> ExternResult<Vec<ActionHash>> {
     let path = Path::from("all_mews");
-    let mut links = get_links(path.path_entry_hash()?, LinkTypes::AllMews, None)?;
+    let mut links = filter_hidden_links(get_links(
+        path.path_entry_hash()?,
+        LinkTypes::AllMews,
+        None,
+    )?)?;
     links.sort_by_key(|a| a.timestamp);
     let hashes: Vec<ActionHash> = links
         .into_iter()
@@ -12,3 +20,20 @@ pub fn get_all_mew_hashes() -> ExternResult<Vec<ActionHash>> {
 
     Ok(hashes)
 }
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetAllMewsPageInput {
+    pub page: Option<CursorPagination>,
+    pub options: Option<FeedMewOptions>,
+}
+#[hdk_extern]
+pub fn get_all_mews_page_with_context(input: GetAllMewsPageInput) -> ExternResult<Page<FeedMew>> {
+    let links = get_mew_links_for_time_buckets(
+        make_all_mews_time_bucket_root(),
+        &input.page,
+        &get_my_hidden_agents()?,
+    )?;
+    let links_page = paginate_by_cursor(links, input.page)?;
+
+    get_page_mews_with_context(links_page, &input.options.unwrap_or_default())
+}
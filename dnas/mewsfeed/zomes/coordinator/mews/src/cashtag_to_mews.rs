@@ -1,5 +1,5 @@
 use crate::tag_to_mews::*;
-use hc_link_pagination::HashPagination;
+use hc_link_pagination::{CursorPagination, HashPagination, Page};
 use hdk::prelude::*;
 use mews_integrity::*;
 
@@ -57,10 +57,34 @@ pub fn remove_cashtag_for_mew(input: RemoveCashtagForMewInput) -> ExternResult<(
 pub struct GetMewsForCashtagWithContextInput {
     cashtag: String,
     page: Option<HashPagination>,
+    options: Option<FeedMewOptions>,
 }
 #[hdk_extern]
 pub fn get_mews_for_cashtag_with_context(
     input: GetMewsForCashtagWithContextInput,
 ) -> ExternResult<Vec<FeedMew>> {
-    get_mews_for_tag_with_context(input.cashtag, LinkTypes::CashtagToMews, input.page)
+    get_mews_for_tag_with_context(
+        input.cashtag,
+        LinkTypes::CashtagToMews,
+        input.page,
+        &input.options.unwrap_or_default(),
+    )
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetMewsForCashtagPageWithContextInput {
+    cashtag: String,
+    page: Option<CursorPagination>,
+    options: Option<FeedMewOptions>,
+}
+#[hdk_extern]
+pub fn get_mews_for_cashtag_page_with_context(
+    input: GetMewsForCashtagPageWithContextInput,
+) -> ExternResult<Page<FeedMew>> {
+    get_mews_for_tag_page_with_context(
+        input.cashtag,
+        LinkTypes::CashtagToMews,
+        input.page,
+        &input.options.unwrap_or_default(),
+    )
 }
@@ -1,14 +1,20 @@
-use crate::mew_with_context::get_batch_mews_with_context;
+use crate::hidden_agents::get_my_hidden_agents;
+use crate::mew_with_context::{get_batch_mews_with_options, get_page_mews_with_context};
+use crate::time_bucket_to_mews::get_mew_links_for_time_buckets;
 use follows_types::GetCreatorsForFollowerInput;
 use hc_call_utils::call_local_zome;
-use hc_link_pagination::{paginate_by_hash, HashPagination};
+use hc_link_pagination::{
+    paginate_by_cursor, paginate_by_hash, CursorPagination, HashPagination, Page,
+};
 use hdk::prelude::*;
 use mews_integrity::*;
+use std::collections::HashSet;
 
 #[derive(Serialize, Deserialize, SerializedBytes, Clone, Debug)]
 pub struct GetFollowedCreatorsMewsInput {
     pub agent: AgentPubKey,
     pub page: Option<HashPagination>,
+    pub options: Option<FeedMewOptions>,
 }
 #[hdk_extern]
 pub fn get_followed_creators_mews(
@@ -29,40 +35,55 @@ pub fn get_followed_creators_mews(
 pub fn get_followed_creators_mews_with_context(
     input: GetFollowedCreatorsMewsInput,
 ) -> ExternResult<Vec<FeedMew>> {
+    let options = input.options.clone().unwrap_or_default();
     let hashes = get_followed_creators_mew_hashes(input)?;
 
-    get_batch_mews_with_context(hashes)
+    get_batch_mews_with_options(hashes, &options)
 }
 
 fn get_followed_creators_mew_hashes(
     input: GetFollowedCreatorsMewsInput,
 ) -> ExternResult<Vec<ActionHash>> {
-    let mut creators: Vec<AgentPubKey> =
-        call_local_zome::<Vec<AgentPubKey>, GetCreatorsForFollowerInput>(
-            "follows",
-            "get_creators_for_follower",
-            GetCreatorsForFollowerInput {
-                follower: input.agent.clone(),
-                page: None,
-            },
-        )?;
-    creators.push(input.agent);
+    let links = get_followed_creators_mew_links(input.agent)?;
+    let links_page = paginate_by_hash(links, input.page)?;
+
+    let hashes: Vec<ActionHash> = links_page
+        .into_iter()
+        .filter_map(|link| ActionHash::try_from(link.target).ok())
+        .collect();
+
+    Ok(hashes)
+}
 
-    let links: Vec<Link> = creators
+fn get_followed_creators_mew_links(agent: AgentPubKey) -> ExternResult<Vec<Link>> {
+    let links: Vec<Link> = get_followed_creators_and_self(agent)?
         .into_iter()
         .filter_map(|agent| {
             get_links(AnyLinkableHash::from(agent), LinkTypes::AgentMews, None).ok()
         })
         .flatten()
         .collect();
-    let links_page = paginate_by_hash(links, input.page)?;
 
-    let hashes: Vec<ActionHash> = links_page
-        .into_iter()
-        .filter_map(|link| ActionHash::try_from(link.target).ok())
-        .collect();
+    Ok(links)
+}
 
-    Ok(hashes)
+/// Creators the agent follows, without those I have blocked or muted or can't see
+fn get_followed_creators_and_self(agent: AgentPubKey) -> ExternResult<Vec<AgentPubKey>> {
+    let hidden_agents = get_my_hidden_agents()?;
+    let mut creators: Vec<AgentPubKey> =
+        call_local_zome::<Vec<AgentPubKey>, GetCreatorsForFollowerInput>(
+            "follows",
+            "get_creators_for_follower",
+            GetCreatorsForFollowerInput {
+                follower: agent.clone(),
+                page: None,
+            },
+        )?;
+    creators.push(agent);
+    creators.retain(|creator| !hidden_agents.contains(creator));
+
+    // Protected creators' mews are only shown to their followers
+    call_local_zome::<Vec<AgentPubKey>, Vec<AgentPubKey>>("follows", "get_visible_agents", creators)
 }
 
 #[hdk_extern]
@@ -72,5 +93,51 @@ pub fn get_my_followed_creators_mews_with_context(
     get_followed_creators_mews_with_context(GetFollowedCreatorsMewsInput {
         agent: agent_info()?.agent_initial_pubkey,
         page,
+        options: None,
+    })
+}
+
+#[derive(Serialize, Deserialize, SerializedBytes, Clone, Debug)]
+pub struct GetFollowedCreatorsMewsPageInput {
+    pub agent: AgentPubKey,
+    pub page: Option<CursorPagination>,
+    pub options: Option<FeedMewOptions>,
+}
+#[hdk_extern]
+pub fn get_followed_creators_mews_page_with_context(
+    input: GetFollowedCreatorsMewsPageInput,
+) -> ExternResult<Page<FeedMew>> {
+    // Each creator's buckets are walked until a full page is found, then merged into one page
+    let links: Vec<Link> = get_followed_creators_and_self(input.agent)?
+        .into_iter()
+        .map(|agent| {
+            get_mew_links_for_time_buckets(
+                make_agent_mews_time_bucket_root(agent),
+                &input.page,
+                &HashSet::new(),
+            )
+        })
+        .collect::<ExternResult<Vec<Vec<Link>>>>()?
+        .into_iter()
+        .flatten()
+        .collect();
+    let links_page = paginate_by_cursor(links, input.page)?;
+
+    get_page_mews_with_context(links_page, &input.options.unwrap_or_default())
+}
+
+#[derive(Serialize, Deserialize, SerializedBytes, Clone, Debug)]
+pub struct GetMyFollowedCreatorsMewsPageInput {
+    pub page: Option<CursorPagination>,
+    pub options: Option<FeedMewOptions>,
+}
+#[hdk_extern]
+pub fn get_my_followed_creators_mews_page_with_context(
+    input: GetMyFollowedCreatorsMewsPageInput,
+) -> ExternResult<Page<FeedMew>> {
+    get_followed_creators_mews_page_with_context(GetFollowedCreatorsMewsPageInput {
+        agent: agent_info()?.agent_initial_pubkey,
+        page: input.page,
+        options: input.options,
     })
 }
@@ -1,5 +1,5 @@
 use crate::tag_to_mews::*;
-use hc_link_pagination::HashPagination;
+use hc_link_pagination::{CursorPagination, HashPagination, Page};
 use hdk::prelude::*;
 use mews_integrity::*;
 
@@ -57,10 +57,34 @@ pub fn remove_hashtag_for_mew(input: RemoveHashtagForMewInput) -> ExternResult<(
 pub struct GetMewsForHashtagWithContextInput {
     hashtag: String,
     page: Option<HashPagination>,
+    options: Option<FeedMewOptions>,
 }
 #[hdk_extern]
 pub fn get_mews_for_hashtag_with_context(
     input: GetMewsForHashtagWithContextInput,
 ) -> ExternResult<Vec<FeedMew>> {
-    get_mews_for_tag_with_context(input.hashtag, LinkTypes::HashtagToMews, input.page)
+    get_mews_for_tag_with_context(
+        input.hashtag,
+        LinkTypes::HashtagToMews,
+        input.page,
+        &input.options.unwrap_or_default(),
+    )
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetMewsForHashtagPageWithContextInput {
+    hashtag: String,
+    page: Option<CursorPagination>,
+    options: Option<FeedMewOptions>,
+}
+#[hdk_extern]
+pub fn get_mews_for_hashtag_page_with_context(
+    input: GetMewsForHashtagPageWithContextInput,
+) -> ExternResult<Page<FeedMew>> {
+    get_mews_for_tag_page_with_context(
+        input.hashtag,
+        LinkTypes::HashtagToMews,
+        input.page,
+        &input.options.unwrap_or_default(),
+    )
 }
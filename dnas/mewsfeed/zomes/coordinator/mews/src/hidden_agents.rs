@@ -0,0 +1,36 @@
+use hc_call_utils::call_local_zome;
+use hdk::prelude::*;
+use std::collections::HashSet;
+
+/// Agents I have blocked or muted, whose mews and notifications are left out for me
+///
+/// The agents muted in my NotificationPreferences only have their notifications left out, so they
+/// aren't included here.
+pub fn get_my_hidden_agents() -> ExternResult<HashSet<AgentPubKey>> {
+    let mut agents: HashSet<AgentPubKey> =
+        call_local_zome::<Vec<AgentPubKey>, ()>("blocks", "get_my_blocked_agents", ())?
+            .into_iter()
+            .collect();
+    agents.extend(call_local_zome::<Vec<AgentPubKey>, ()>(
+        "blocks",
+        "get_my_muted_agents",
+        (),
+    )?);
+
+    Ok(agents)
+}
+
+/// Leave out links authored by agents I have blocked or muted
+///
+/// Links to mews are created by the mew's author, so this filters before pagination keeps pages full.
+pub fn filter_hidden_links(links: Vec<Link>) -> ExternResult<Vec<Link>> {
+    let hidden_agents = get_my_hidden_agents()?;
+    if hidden_agents.is_empty() {
+        return Ok(links);
+    }
+
+    Ok(links
+        .into_iter()
+        .filter(|link| !hidden_agents.contains(&link.author))
+        .collect())
+}
@@ -1,21 +1,37 @@
 use hdk::prelude::*;
+use std::collections::BTreeSet;
 pub mod agent_mews;
 pub mod agent_to_notifications;
 pub mod all_mews;
 pub mod cashtag_to_mews;
 pub mod followed_creators_mews;
 pub mod hashtag_to_mews;
+pub mod hidden_agents;
 pub mod licker_to_mews;
 pub mod mention_to_mews;
 pub mod mew;
 pub mod mew_to_responses;
 pub mod mew_with_context;
+pub mod notification_preferences;
+pub mod notification_signals;
+pub mod notifications_read;
 pub mod pinner_to_mews;
 pub mod random_mews;
 pub mod search_tags;
 pub mod tag_to_mews;
+pub mod time_bucket_to_mews;
+pub mod yarn;
 
 #[hdk_extern]
 pub fn init(_: ()) -> ExternResult<InitCallbackResult> {
+    // Any agent can push notifications to me
+    let mut functions = BTreeSet::new();
+    functions.insert((zome_info()?.name, FunctionName::from("recv_remote_signal")));
+    create_cap_grant(CapGrantEntry {
+        tag: String::from("recv_remote_signal"),
+        access: CapAccess::Unrestricted,
+        functions: GrantedFunctions::Listed(functions),
+    })?;
+
     Ok(InitCallbackResult::Pass)
 }
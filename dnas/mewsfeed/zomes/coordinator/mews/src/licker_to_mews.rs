@@ -1,5 +1,6 @@
 use hc_call_utils::call_local_zome;
 use hdk::prelude::*;
+use mews_types::ReactionSummary;
 
 #[hdk_extern]
 pub fn get_lickers_for_mew(mew_hash: ActionHash) -> ExternResult<Vec<AgentPubKey>> {
@@ -20,3 +21,13 @@ pub struct IsLikerForHashInput {
 pub fn is_licker_for_mew(input: IsLikerForHashInput) -> ExternResult<bool> {
     call_local_zome::<bool, IsLikerForHashInput>("likes", "is_liker_for_hash", input)
 }
+
+pub fn get_reaction_summaries_for_mews(
+    mew_hashes: Vec<ActionHash>,
+) -> ExternResult<Vec<ReactionSummary>> {
+    call_local_zome::<Vec<ReactionSummary>, Vec<ActionHash>>(
+        "likes",
+        "get_reaction_summaries_for_hashes",
+        mew_hashes,
+    )
+}
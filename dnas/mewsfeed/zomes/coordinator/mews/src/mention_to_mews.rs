@@ -1,5 +1,9 @@
-use crate::mew_with_context::get_batch_mews_with_context;
-use hc_link_pagination::{paginate_by_hash, HashPagination};
+use crate::agent_to_notifications::add_notification;
+use crate::hidden_agents::filter_hidden_links;
+use crate::mew_with_context::{get_batch_mews_with_options, get_page_mews_with_context};
+use hc_link_pagination::{
+    paginate_by_cursor, paginate_by_hash, CursorPagination, HashPagination, Page,
+};
 use hdk::prelude::*;
 use mews_integrity::*;
 
@@ -10,12 +14,24 @@ pub struct AddMentionForMewInput {
 }
 #[hdk_extern]
 pub fn add_mention_for_mew(input: AddMentionForMewInput) -> ExternResult<()> {
-    create_link(
-        input.base_mention,
-        input.target_mew_hash,
+    let link_hash = create_link(
+        input.base_mention.clone(),
+        input.target_mew_hash.clone(),
         LinkTypes::MentionToMews,
         (),
     )?;
+    add_notification(AddNotificationInput {
+        notification_type: NotificationType::MyAgentMentioned,
+        recipient: Some(input.base_mention.clone()),
+        feed_mew_hash: Some(input.target_mew_hash.clone()),
+        action_hash: link_hash,
+        yarn_response_hash: None,
+    })?;
+    push_notification(SendNotificationSignalInput {
+        notification_type: NotificationType::MyAgentMentioned,
+        recipient: Some(input.base_mention),
+        feed_mew_hash: Some(input.target_mew_hash),
+    });
 
     Ok(())
 }
@@ -47,6 +63,7 @@ pub fn get_mews_for_mention(input: GetMewsForMentionInput) -> ExternResult<Vec<R
 pub struct GetMewsForMentionWithContextInput {
     mention: AgentPubKey,
     page: Option<HashPagination>,
+    options: Option<FeedMewOptions>,
 }
 #[hdk_extern]
 pub fn get_mews_for_mention_with_context(
@@ -54,14 +71,15 @@ pub fn get_mews_for_mention_with_context(
 ) -> ExternResult<Vec<FeedMew>> {
     let hashes = get_mew_hashes_for_mention(input.mention, input.page)?;
 
-    get_batch_mews_with_context(hashes)
+    get_batch_mews_with_options(hashes, &input.options.unwrap_or_default())
 }
 
 fn get_mew_hashes_for_mention(
     mention: AgentPubKey,
     page: Option<HashPagination>,
 ) -> ExternResult<Vec<ActionHash>> {
-    let links: Vec<Link> = get_links(mention, LinkTypes::MentionToMews, None)?;
+    let links: Vec<Link> =
+        filter_hidden_links(get_links(mention, LinkTypes::MentionToMews, None)?)?;
     let links_page = paginate_by_hash(links, page)?;
 
     let hashes: Vec<ActionHash> = links_page
@@ -72,6 +90,23 @@ fn get_mew_hashes_for_mention(
     Ok(hashes)
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetMewsForMentionPageWithContextInput {
+    mention: AgentPubKey,
+    page: Option<CursorPagination>,
+    options: Option<FeedMewOptions>,
+}
+#[hdk_extern]
+pub fn get_mews_for_mention_page_with_context(
+    input: GetMewsForMentionPageWithContextInput,
+) -> ExternResult<Page<FeedMew>> {
+    let links: Vec<Link> =
+        filter_hidden_links(get_links(input.mention, LinkTypes::MentionToMews, None)?)?;
+    let links_page = paginate_by_cursor(links, input.page)?;
+
+    get_page_mews_with_context(links_page, &input.options.unwrap_or_default())
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct RemoveMentionForMewInput {
     pub base_mention: AgentPubKey,
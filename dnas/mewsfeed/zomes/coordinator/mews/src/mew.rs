@@ -2,10 +2,10 @@ use crate::cashtag_to_mews::*;
 use crate::hashtag_to_mews::*;
 use crate::mention_to_mews::*;
 use crate::mew_to_responses::*;
-use crate::mew_with_context::get_mew_with_context;
+use crate::mew_with_context::get_mew_with_options;
+use crate::time_bucket_to_mews::*;
 use hdk::prelude::*;
 use mews_integrity::*;
-use regex::Regex;
 
 #[hdk_extern]
 pub fn create_mew(mew: Mew) -> ExternResult<ActionHash> {
@@ -18,8 +18,26 @@ pub fn create_mew(mew: Mew) -> ExternResult<ActionHash> {
         (),
     )?;
     let my_agent_pub_key = agent_info()?.agent_latest_pubkey;
-    create_link(my_agent_pub_key, mew_hash.clone(), LinkTypes::AgentMews, ())?;
-    add_tags_for_mew(mew.clone(), mew_hash.clone())?;
+    create_link(
+        my_agent_pub_key.clone(),
+        mew_hash.clone(),
+        LinkTypes::AgentMews,
+        (),
+    )?;
+    // The new action is read back from my own chain, without going to the network
+    let record = query(ChainQueryFilter::new().sequence_range(
+        ChainQueryFilterRange::ActionHashTerminated(mew_hash.clone(), 0),
+    ))?
+    .pop()
+    .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+        "Could not find the newly created mew"
+    ))))?;
+    add_time_buckets_for_mew(
+        my_agent_pub_key,
+        mew_hash.clone(),
+        record.action().timestamp(),
+    )?;
+    add_tags_for_mew(get_mew_tags(&mew)?, mew_hash.clone())?;
 
     match mew.mew_type {
         MewType::Quote(base_original_mew_hash) => {
@@ -52,7 +70,7 @@ pub fn create_mew(mew: Mew) -> ExternResult<ActionHash> {
 #[hdk_extern]
 pub fn create_mew_with_context(mew: Mew) -> ExternResult<FeedMew> {
     let action_hash = create_mew(mew)?;
-    get_mew_with_context(action_hash)
+    get_mew_with_options(action_hash, &FeedMewOptions::default())
 }
 
 #[hdk_extern]
@@ -60,18 +78,119 @@ pub fn get_mew(original_mew_hash: ActionHash) -> ExternResult<Option<Record>> {
     get(original_mew_hash, GetOptions::default())
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateMewInput {
+    pub original_mew_hash: ActionHash,
+    pub updated_mew: Mew,
+}
+#[hdk_extern]
+pub fn update_mew(input: UpdateMewInput) -> ExternResult<ActionHash> {
+    let previous_mew = get_latest_mew(input.original_mew_hash.clone())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest(String::from("Mew not found"))
+    ))?;
+    let updated_mew_hash = update_entry(input.original_mew_hash.clone(), &input.updated_mew)?;
+
+    // Only re-index the tags and mentions that changed between revisions
+    let previous_tags = get_mew_tags(&previous_mew)?;
+    let updated_tags = get_mew_tags(&input.updated_mew)?;
+    remove_tags_for_mew(
+        previous_tags.difference(&updated_tags),
+        input.original_mew_hash.clone(),
+    )?;
+    add_tags_for_mew(
+        updated_tags.difference(&previous_tags),
+        input.original_mew_hash,
+    )?;
+
+    Ok(updated_mew_hash)
+}
+
+#[hdk_extern]
+pub fn update_mew_with_context(input: UpdateMewInput) -> ExternResult<FeedMew> {
+    let original_mew_hash = input.original_mew_hash.clone();
+    update_mew(input)?;
+    get_mew_with_options(original_mew_hash, &FeedMewOptions::default())
+}
+
+/// All revisions of a mew, oldest first, starting with the original
+#[hdk_extern]
+pub fn get_mew_revisions(original_mew_hash: ActionHash) -> ExternResult<Vec<Record>> {
+    let details = get_details(original_mew_hash, GetOptions::default())?;
+    let (record, mut updates) = match details {
+        Some(Details::Record(RecordDetails {
+            record, updates, ..
+        })) => (record, updates),
+        _ => return Ok(vec![]),
+    };
+    updates.sort_by_key(|update| update.action().timestamp());
+
+    let get_input: Vec<GetInput> = updates
+        .into_iter()
+        .map(|update| GetInput::new(update.as_hash().clone().into(), GetOptions::default()))
+        .collect();
+    let mut revisions = vec![record];
+    revisions.extend(
+        HDK.with(|hdk| hdk.borrow().get(get_input))?
+            .into_iter()
+            .flatten(),
+    );
+
+    Ok(revisions)
+}
+
+/// Latest revision of a mew, following the updates of its original create action
+pub fn get_latest_mew(original_mew_hash: ActionHash) -> ExternResult<Option<Mew>> {
+    match get_details(original_mew_hash, GetOptions::default())? {
+        Some(Details::Record(RecordDetails {
+            record, updates, ..
+        })) => match get_latest_mew_revision(&updates)? {
+            Some((mew, _)) => Ok(Some(mew)),
+            None => record.entry().to_app_option().map_err(|e| wasm_error!(e)),
+        },
+        _ => Ok(None),
+    }
+}
+
+/// Content and timestamp of the most recent update, if the mew has been edited
+pub fn get_latest_mew_revision(
+    updates: &[SignedActionHashed],
+) -> ExternResult<Option<(Mew, Timestamp)>> {
+    match updates
+        .iter()
+        .max_by_key(|update| update.action().timestamp())
+    {
+        Some(latest_update) => {
+            let record =
+                get(latest_update.as_hash().clone(), GetOptions::default())?.ok_or(wasm_error!(
+                    WasmErrorInner::Guest(String::from("Mew revision not found"))
+                ))?;
+            let mew: Mew = record
+                .entry()
+                .to_app_option()
+                .map_err(|e| wasm_error!(e))?
+                .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                    "Malformed mew revision"
+                ))))?;
+
+            Ok(Some((mew, latest_update.action().timestamp())))
+        }
+        None => Ok(None),
+    }
+}
+
 #[hdk_extern]
 pub fn delete_mew(original_mew_hash: ActionHash) -> ExternResult<ActionHash> {
     let maybe_record = get(original_mew_hash.clone(), GetOptions::default())?;
     if let Some(record) = maybe_record {
-        let mew = record
-            .entry()
-            .to_app_option()
-            .map_err(|e| wasm_error!(e))?
-            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
-                "Linked action must reference an entry"
-            ))))?;
-        remove_tags_for_mew(mew, original_mew_hash.clone())?;
+        let mew = get_latest_mew(original_mew_hash.clone())?.ok_or(wasm_error!(
+            WasmErrorInner::Guest(String::from("Linked action must reference an entry"))
+        ))?;
+        remove_tags_for_mew(get_mew_tags(&mew)?, original_mew_hash.clone())?;
+        remove_time_buckets_for_mew(
+            record.action().author().clone(),
+            original_mew_hash.clone(),
+            record.action().timestamp(),
+        )?;
     }
 
     let path_hash = Path::from("all_mews").path_entry_hash()?;
@@ -106,70 +225,98 @@ pub fn delete_mew(original_mew_hash: ActionHash) -> ExternResult<ActionHash> {
     delete_entry(original_mew_hash)
 }
 
-fn add_tags_for_mew(mew: Mew, mew_hash: ActionHash) -> ExternResult<()> {
-    let (hashtag_regex, cashtag_regex) = tag_regexes()?;
-    for regex_match in hashtag_regex.find_iter(&mew.text) {
+#[derive(Clone, Debug, Default)]
+struct MewTags {
+    hashtags: Vec<String>,
+    cashtags: Vec<String>,
+    mentions: Vec<AgentPubKey>,
+}
+
+impl MewTags {
+    /// Tags present in self but not in other
+    fn difference(&self, other: &MewTags) -> MewTags {
+        MewTags {
+            hashtags: self
+                .hashtags
+                .iter()
+                .filter(|tag| !other.hashtags.contains(tag))
+                .cloned()
+                .collect(),
+            cashtags: self
+                .cashtags
+                .iter()
+                .filter(|tag| !other.cashtags.contains(tag))
+                .cloned()
+                .collect(),
+            mentions: self
+                .mentions
+                .iter()
+                .filter(|agent| !other.mentions.contains(agent))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+fn get_mew_tags(mew: &Mew) -> ExternResult<MewTags> {
+    let mut tags = MewTags {
+        hashtags: get_hashtags(&mew.text),
+        cashtags: get_cashtags(&mew.text),
+        ..Default::default()
+    };
+    for link in mew.links.iter() {
+        if let LinkTarget::Mention(mention) = link {
+            if !tags.mentions.contains(mention) {
+                tags.mentions.push(mention.clone());
+            }
+        }
+    }
+
+    Ok(tags)
+}
+
+fn add_tags_for_mew(tags: MewTags, mew_hash: ActionHash) -> ExternResult<()> {
+    for hashtag in tags.hashtags {
         add_hashtag_for_mew(AddHashtagForMewInput {
-            base_hashtag: regex_match.as_str().into(),
+            base_hashtag: hashtag,
             target_mew_hash: mew_hash.clone(),
         })?;
     }
-    for regex_match in cashtag_regex.find_iter(&mew.text) {
-        // TODO invalid commit errors
-        // add_cashtag_for_mew(AddCashtagForMewInput {
-        //     base_cashtag: regex_match.as_str().into(),
-        //     target_mew_hash: mew_hash.clone(),
-        // })?;
+    for cashtag in tags.cashtags {
+        add_cashtag_for_mew(AddCashtagForMewInput {
+            base_cashtag: cashtag,
+            target_mew_hash: mew_hash.clone(),
+        })?;
     }
-    for link in mew.links {
-        if let LinkTarget::Mention(mention) = link {
-            add_mention_for_mew(AddMentionForMewInput {
-                base_mention: mention,
-                target_mew_hash: mew_hash.clone(),
-            })?;
-        }
+    for mention in tags.mentions {
+        add_mention_for_mew(AddMentionForMewInput {
+            base_mention: mention,
+            target_mew_hash: mew_hash.clone(),
+        })?;
     }
 
     Ok(())
 }
 
-fn remove_tags_for_mew(mew: Mew, mew_hash: ActionHash) -> ExternResult<()> {
-    let (hashtag_regex, cashtag_regex) = tag_regexes()?;
-    for regex_match in hashtag_regex.find_iter(&mew.text) {
+fn remove_tags_for_mew(tags: MewTags, mew_hash: ActionHash) -> ExternResult<()> {
+    for hashtag in tags.hashtags {
         remove_hashtag_for_mew(RemoveHashtagForMewInput {
-            base_hashtag: regex_match.as_str().into(),
+            base_hashtag: hashtag,
             target_mew_hash: mew_hash.clone(),
         })?;
     }
-    for regex_match in cashtag_regex.find_iter(&mew.text) {
+    for cashtag in tags.cashtags {
         remove_cashtag_for_mew(RemoveCashtagForMewInput {
-            base_cashtag: regex_match.as_str().into(),
+            base_cashtag: cashtag,
             target_mew_hash: mew_hash.clone(),
         })?;
     }
-    for link in mew.links {
-        if let LinkTarget::Mention(base_mention) = link {
-            remove_mention_for_mew(RemoveMentionForMewInput {
-                base_mention,
-                target_mew_hash: mew_hash.clone(),
-            })?;
-        }
+    for base_mention in tags.mentions {
+        remove_mention_for_mew(RemoveMentionForMewInput {
+            base_mention,
+            target_mew_hash: mew_hash.clone(),
+        })?;
     }
 
     Ok(())
 }
-
-fn tag_regexes() -> ExternResult<(Regex, Regex)> {
-    let hashtag_regex = Regex::new(r"#\w+").map_err(|_| {
-        wasm_error!(WasmErrorInner::Guest(
-            "Failed to create hashtag regex".into()
-        ))
-    })?;
-    let cashtag_regex = Regex::new(r"\$\w+").map_err(|_| {
-        wasm_error!(WasmErrorInner::Guest(
-            "Failed to create cashtag regex".into()
-        ))
-    })?;
-
-    Ok((hashtag_regex, cashtag_regex))
-}
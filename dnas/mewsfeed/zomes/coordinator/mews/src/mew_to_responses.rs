@@ -1,6 +1,10 @@
+use crate::agent_to_notifications::add_notification;
+use crate::hidden_agents::filter_hidden_links;
 use hc_link_pagination::{paginate_by_hash, HashPagination};
+use hc_link_type_utils::resolve_link_type;
 use hdk::prelude::*;
 use mews_integrity::*;
+use std::collections::HashSet;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct AddResponseForMewInput {
@@ -16,12 +20,63 @@ pub fn add_response_for_mew(input: AddResponseForMewInput) -> ExternResult<()> {
         ))
     })?;
 
-    create_link(
+    let link_hash = create_link(
         input.base_original_mew_hash.clone(),
-        input.target_response_mew_hash,
+        input.target_response_mew_hash.clone(),
         LinkTypes::MewToResponses,
         tag.bytes().clone(),
     )?;
+
+    if let Some(original_record) = get(input.base_original_mew_hash.clone(), GetOptions::default())?
+    {
+        let original_author = original_record.action().author().clone();
+        add_notification(AddNotificationInput {
+            notification_type: NotificationType::MyMewResponded,
+            recipient: Some(original_author.clone()),
+            feed_mew_hash: Some(input.target_response_mew_hash.clone()),
+            action_hash: link_hash.clone(),
+            yarn_response_hash: None,
+        })?;
+        push_notification(SendNotificationSignalInput {
+            notification_type: NotificationType::MyMewResponded,
+            recipient: Some(original_author.clone()),
+            feed_mew_hash: Some(input.target_response_mew_hash.clone()),
+        });
+
+        // The other agents who responded to the mew before me follow its yarn
+        let my_pubkey = agent_info()?.agent_initial_pubkey;
+        let mut yarn_followers: HashSet<AgentPubKey> = HashSet::new();
+        for link in get_response_links_for_mew(input.base_original_mew_hash, None)? {
+            if link.author == my_pubkey
+                || link.author == original_author
+                || yarn_followers.contains(&link.author)
+            {
+                continue;
+            }
+            // Anyone can link a response, so only the response's own author follows the yarn
+            let response_hash =
+                ActionHash::try_from(link.target.clone()).map_err(|err| wasm_error!(err))?;
+            match get(response_hash.clone(), GetOptions::default())? {
+                Some(response) if *response.action().author() == link.author => {}
+                _ => continue,
+            }
+            yarn_followers.insert(link.author.clone());
+
+            add_notification(AddNotificationInput {
+                notification_type: NotificationType::FollowedYarnResponded,
+                recipient: Some(link.author.clone()),
+                feed_mew_hash: Some(input.target_response_mew_hash.clone()),
+                action_hash: link_hash.clone(),
+                yarn_response_hash: Some(response_hash),
+            })?;
+            push_notification(SendNotificationSignalInput {
+                notification_type: NotificationType::FollowedYarnResponded,
+                recipient: Some(link.author),
+                feed_mew_hash: Some(input.target_response_mew_hash.clone()),
+            });
+        }
+    }
+
     Ok(())
 }
 
@@ -30,12 +85,30 @@ pub struct GetResponsesForMewInput {
     pub original_mew_hash: ActionHash,
     pub response_type: Option<ResponseType>,
     pub page: Option<HashPagination>,
+    pub options: Option<FeedMewOptions>,
 }
 #[hdk_extern]
 pub fn get_response_hashes_for_mew(
     input: GetResponsesForMewInput,
 ) -> ExternResult<Vec<ActionHash>> {
-    let tag = match input.response_type {
+    let links = filter_hidden_links(get_response_links_for_mew(
+        input.original_mew_hash,
+        input.response_type,
+    )?)?;
+    let links_page = paginate_by_hash(links, input.page)?;
+    let hashes: Vec<ActionHash> = links_page
+        .into_iter()
+        .filter_map(|link| ActionHash::try_from(link.target).ok())
+        .collect();
+
+    Ok(hashes)
+}
+
+pub fn get_response_links_for_mew(
+    original_mew_hash: ActionHash,
+    response_type: Option<ResponseType>,
+) -> ExternResult<Vec<Link>> {
+    let tag = match response_type {
         Some(response_type) => {
             let tag: SerializedBytes = response_type.try_into().map_err(|_| {
                 wasm_error!(WasmErrorInner::Guest(
@@ -48,14 +121,7 @@ pub fn get_response_hashes_for_mew(
         None => None,
     };
 
-    let links = get_links(input.original_mew_hash, LinkTypes::MewToResponses, tag)?;
-    let links_page = paginate_by_hash(links, input.page)?;
-    let hashes: Vec<ActionHash> = links_page
-        .into_iter()
-        .filter_map(|link| ActionHash::try_from(link.target).ok())
-        .collect();
-
-    Ok(hashes)
+    get_links(original_mew_hash, LinkTypes::MewToResponses, tag)
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -80,10 +146,7 @@ pub fn count_responses_for_mew(input: CountResponsesForMewInput) -> ExternResult
 
     let mut query = LinkQuery::new(
         input.original_mew_hash,
-        LinkTypeFilter::single_type(
-            ZomeIndex(1),
-            LinkType(5), // LinkTypes::MewToResponses
-        ),
+        resolve_link_type("mews_integrity", LinkTypes::MewToResponses)?.filter(),
     );
 
     if let Some(tag) = maybe_tag {
@@ -116,10 +179,7 @@ pub fn get_response_for_mew_exists(input: GetResponseForMewExistsInput) -> Exter
 
     let mut query = LinkQuery::new(
         input.original_mew_hash.clone(),
-        LinkTypeFilter::single_type(
-            ZomeIndex(1),
-            LinkType(5), // LinkTypes::MewToResponses
-        ),
+        resolve_link_type("mews_integrity", LinkTypes::MewToResponses)?.filter(),
     )
     .author(input.response_author);
 
@@ -1,167 +1,426 @@
 use crate::licker_to_mews::*;
+use crate::mew::get_latest_mew_revision;
 use crate::mew_to_responses::*;
-use crate::pinner_to_mews::get_is_hash_pinned;
+use crate::pinner_to_mews::get_my_pinned_hashes;
 use hc_call_utils::call_local_zome;
+use hc_link_pagination::Page;
 use hdk::prelude::*;
 use mews_integrity::*;
 use mews_types::Profile;
+use std::collections::HashMap;
 
+/// A mew hash on its own gets the mew with its full context
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum GetMewWithContextInput {
+    MewHash(ActionHash),
+    WithOptions {
+        mew_hash: ActionHash,
+        options: FeedMewOptions,
+    },
+}
 #[hdk_extern]
-pub fn get_mew_with_context(original_mew_hash: ActionHash) -> ExternResult<FeedMew> {
-    let response = get_details(original_mew_hash.clone(), GetOptions::default())?.ok_or(
-        wasm_error!(WasmErrorInner::Guest(String::from("Mew not found"))),
-    )?;
+pub fn get_mew_with_context(input: GetMewWithContextInput) -> ExternResult<FeedMew> {
+    match input {
+        GetMewWithContextInput::MewHash(mew_hash) => {
+            get_mew_with_options(mew_hash, &FeedMewOptions::default())
+        }
+        GetMewWithContextInput::WithOptions { mew_hash, options } => {
+            get_mew_with_options(mew_hash, &options)
+        }
+    }
+}
+
+/// A list of mew hashes on its own gets the mews with their full context
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum GetBatchMewsWithContextInput {
+    MewHashes(Vec<ActionHash>),
+    WithOptions {
+        mew_hashes: Vec<ActionHash>,
+        options: FeedMewOptions,
+    },
+}
+#[hdk_extern]
+pub fn get_batch_mews_with_context(
+    input: GetBatchMewsWithContextInput,
+) -> ExternResult<Vec<FeedMew>> {
+    match input {
+        GetBatchMewsWithContextInput::MewHashes(mew_hashes) => {
+            get_batch_mews_with_options(mew_hashes, &FeedMewOptions::default())
+        }
+        GetBatchMewsWithContextInput::WithOptions {
+            mew_hashes,
+            options,
+        } => get_batch_mews_with_options(mew_hashes, &options),
+    }
+}
+
+pub fn get_mew_with_options(
+    original_mew_hash: ActionHash,
+    options: &FeedMewOptions,
+) -> ExternResult<FeedMew> {
+    get_batch_mews_with_options(vec![original_mew_hash], options)?
+        .pop()
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Mew not found"
+        ))))
+}
+
+/// Get many mews with their context, sharing lookups between them
+///
+/// Records are fetched with a single multi-get, the agent's pins and the reactions to every mew
+/// are fetched once, and author profiles and embedded mews are fetched once however often they
+/// appear.
+pub fn get_batch_mews_with_options(
+    hashes: Vec<ActionHash>,
+    options: &FeedMewOptions,
+) -> ExternResult<Vec<FeedMew>> {
+    if hashes.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut cache = MewContextCache::new(options, &hashes)?;
+    let records_details = get_batch_record_details(hashes.clone())?
+        .into_iter()
+        .map(|maybe_details| {
+            maybe_details.ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                "Mew not found"
+            ))))
+        })
+        .collect::<ExternResult<Vec<RecordDetails>>>()?;
+
+    let mews = records_details
+        .iter()
+        .map(get_latest_mew_for_details)
+        .collect::<ExternResult<Vec<(Mew, Option<Timestamp>)>>>()?;
+
+    // Embedded mews are fetched once, reusing the records already in the batch
+    let mut embed_hashes: Vec<ActionHash> = vec![];
+    for (mew, _) in mews.iter().filter(|_| options.include_embed) {
+        if let Some(hash) = get_original_mew_hash(mew) {
+            if !hashes.contains(&hash) && !embed_hashes.contains(&hash) {
+                embed_hashes.push(hash);
+            }
+        }
+    }
+    let fetched_embeds_details = get_batch_record_details(embed_hashes.clone())?;
+    let mut embeds_details: HashMap<ActionHash, &RecordDetails> =
+        hashes.iter().cloned().zip(records_details.iter()).collect();
+    for (hash, maybe_details) in embed_hashes.into_iter().zip(fetched_embeds_details.iter()) {
+        if let Some(details) = maybe_details {
+            embeds_details.insert(hash, details);
+        }
+    }
+
+    let mut embeds: HashMap<ActionHash, EmbedMew> = HashMap::new();
+    let mut feed_mews: Vec<FeedMew> = vec![];
+    for ((original_mew_hash, record_details), (mew, edited_timestamp)) in
+        hashes.into_iter().zip(records_details.iter()).zip(mews)
+    {
+        let original_mew = match get_original_mew_hash(&mew).filter(|_| options.include_embed) {
+            Some(hash) => match embeds.get(&hash) {
+                Some(embed) => Some(embed.clone()),
+                None => {
+                    let embed_details =
+                        embeds_details
+                            .get(&hash)
+                            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                                "Original mew not found"
+                            ))))?;
+                    let embed = make_embed_mew(embed_details, &mut cache)?;
+                    embeds.insert(hash, embed.clone());
+
+                    Some(embed)
+                }
+            },
+            None => None,
+        };
+
+        feed_mews.push(make_feed_mew(
+            original_mew_hash,
+            record_details,
+            mew,
+            edited_timestamp,
+            original_mew,
+            options,
+            &mut cache,
+        )?);
+    }
+
+    Ok(feed_mews)
+}
+
+/// Lookups about the calling agent and mew authors, shared by every mew in a batch
+struct MewContextCache {
+    my_pubkey: AgentPubKey,
+    my_pinned_hashes: Vec<AnyLinkableHash>,
+    reaction_summaries: HashMap<ActionHash, ReactionSummary>,
+    include_author_profile: bool,
+    profiles: HashMap<AgentPubKey, Option<Profile>>,
+}
+
+impl MewContextCache {
+    fn new(options: &FeedMewOptions, hashes: &[ActionHash]) -> ExternResult<Self> {
+        let my_pinned_hashes = match options.include_viewer_flags {
+            true => get_my_pinned_hashes()?,
+            false => vec![],
+        };
+        let reaction_summaries = match options.include_counts || options.include_viewer_flags {
+            true => hashes
+                .iter()
+                .cloned()
+                .zip(get_reaction_summaries_for_mews(hashes.to_vec())?)
+                .collect(),
+            false => HashMap::new(),
+        };
+
+        Ok(MewContextCache {
+            my_pubkey: agent_info()?.agent_initial_pubkey,
+            my_pinned_hashes,
+            reaction_summaries,
+            include_author_profile: options.include_author_profile,
+            profiles: HashMap::new(),
+        })
+    }
+
+    fn get_agent_profile(&mut self, agent_pub_key: AgentPubKey) -> ExternResult<Option<Profile>> {
+        if !self.include_author_profile {
+            return Ok(None);
+        }
+        if let Some(profile) = self.profiles.get(&agent_pub_key) {
+            return Ok(profile.clone());
+        }
+
+        let profile = get_agent_profile(agent_pub_key.clone())?;
+        self.profiles.insert(agent_pub_key, profile.clone());
+
+        Ok(profile)
+    }
+}
+
+#[derive(Default)]
+struct ResponsesContext {
+    replies_count: usize,
+    quotes_count: usize,
+    mewmews_count: usize,
+    is_replied: bool,
+    is_quoted: bool,
+    is_mewmewed: bool,
+}
+
+/// Count all response types and find the agent's own responses from a single link query
+fn get_responses_context(
+    original_mew_hash: ActionHash,
+    my_pubkey: &AgentPubKey,
+) -> ExternResult<ResponsesContext> {
+    let mut context = ResponsesContext::default();
+    for link in get_response_links_for_mew(original_mew_hash, None)? {
+        let is_mine = link.author == *my_pubkey;
+        let response_type = ResponseType::try_from(SerializedBytes::from(UnsafeBytes::from(
+            link.tag.into_inner(),
+        )));
+
+        match response_type {
+            Ok(ResponseType::Reply) => {
+                context.replies_count += 1;
+                context.is_replied |= is_mine;
+            }
+            Ok(ResponseType::Quote) => {
+                context.quotes_count += 1;
+                context.is_quoted |= is_mine;
+            }
+            Ok(ResponseType::Mewmew) => {
+                context.mewmews_count += 1;
+                context.is_mewmewed |= is_mine;
+            }
+            Err(_) => (),
+        }
+    }
+
+    Ok(context)
+}
+
+/// Responses and licks counted for a mew
+struct FeedMewCounts {
+    replies: usize,
+    quotes: usize,
+    licks: usize,
+    mewmews: usize,
+}
+
+/// What the calling agent has done with a mew
+struct FeedMewViewerFlags {
+    is_pinned: bool,
+    is_licked: bool,
+    is_mewmewed: bool,
+    is_replied: bool,
+    is_quoted: bool,
+}
+
+fn make_feed_mew(
+    original_mew_hash: ActionHash,
+    record_details: &RecordDetails,
+    mew: Mew,
+    edited_timestamp: Option<Timestamp>,
+    original_mew: Option<EmbedMew>,
+    options: &FeedMewOptions,
+    cache: &mut MewContextCache,
+) -> ExternResult<FeedMew> {
+    let record = &record_details.record;
+    let responses = match options.include_counts || options.include_viewer_flags {
+        true => Some(get_responses_context(
+            original_mew_hash.clone(),
+            &cache.my_pubkey,
+        )?),
+        false => None,
+    };
+    let reactions = match options.include_counts || options.include_viewer_flags {
+        true => Some(
+            cache
+                .reaction_summaries
+                .get(&original_mew_hash)
+                .cloned()
+                .unwrap_or_default(),
+        ),
+        false => None,
+    };
+    let counts = match (options.include_counts, &responses, &reactions) {
+        (true, Some(responses), Some(reactions)) => Some(FeedMewCounts {
+            replies: responses.replies_count,
+            quotes: responses.quotes_count,
+            licks: reactions.counts.get(LICK_REACTION).copied().unwrap_or(0),
+            mewmews: responses.mewmews_count,
+        }),
+        _ => None,
+    };
+
+    let hash: AnyLinkableHash = original_mew_hash.into();
+    let viewer_flags = match (options.include_viewer_flags, &responses, &reactions) {
+        (true, Some(responses), Some(reactions)) => Some(FeedMewViewerFlags {
+            is_pinned: cache.my_pinned_hashes.contains(&hash),
+            is_licked: reactions
+                .my_reactions
+                .iter()
+                .any(|reaction| reaction == LICK_REACTION),
+            is_mewmewed: responses.is_mewmewed,
+            is_replied: responses.is_replied,
+            is_quoted: responses.is_quoted,
+        }),
+        _ => None,
+    };
+
+    let deleted_timestamp = record_details
+        .deletes
+        .first()
+        .map(|first_delete| first_delete.action().timestamp());
+    let author_profile = cache.get_agent_profile(record.action().author().clone())?;
+
+    Ok(FeedMew {
+        mew,
+        action: record.action().clone(),
+        action_hash: record.signed_action().as_hash().clone(),
+        replies_count: counts.as_ref().map(|c| c.replies),
+        quotes_count: counts.as_ref().map(|c| c.quotes),
+        licks_count: counts.as_ref().map(|c| c.licks),
+        mewmews_count: counts.as_ref().map(|c| c.mewmews),
+        deleted_timestamp,
+        edited_timestamp,
+        author_profile,
+        is_pinned: viewer_flags.as_ref().map(|f| f.is_pinned),
+        is_licked: viewer_flags.as_ref().map(|f| f.is_licked),
+        is_mewmewed: viewer_flags.as_ref().map(|f| f.is_mewmewed),
+        is_replied: viewer_flags.as_ref().map(|f| f.is_replied),
+        is_quoted: viewer_flags.as_ref().map(|f| f.is_quoted),
+        original_mew,
+        reactions: reactions
+            .as_ref()
+            .filter(|_| options.include_counts)
+            .map(|reactions| reactions.counts.clone()),
+        my_reactions: reactions
+            .filter(|_| options.include_viewer_flags)
+            .map(|reactions| reactions.my_reactions),
+    })
+}
+
+fn make_embed_mew(
+    record_details: &RecordDetails,
+    cache: &mut MewContextCache,
+) -> ExternResult<EmbedMew> {
+    let (mew, _) = get_latest_mew_for_details(record_details)?;
+    let record = &record_details.record;
+
+    Ok(EmbedMew {
+        mew,
+        action: record.action().clone(),
+        action_hash: record.action_hashed().clone().hash,
+        author_profile: cache.get_agent_profile(record.action().author().clone())?,
+        deleted_timestamp: record_details
+            .deletes
+            .first()
+            .map(|first_delete| first_delete.action().timestamp()),
+    })
+}
+
+fn get_batch_record_details(hashes: Vec<ActionHash>) -> ExternResult<Vec<Option<RecordDetails>>> {
+    let get_input: Vec<GetInput> = hashes
+        .into_iter()
+        .map(|hash| GetInput::new(hash.into(), GetOptions::default()))
+        .collect();
+    let details = HDK.with(|hdk| hdk.borrow().get_details(get_input))?;
 
-    match response {
-        Details::Record(RecordDetails {
-            record, deletes, ..
-        }) => {
-            let mew: Mew = record
+    Ok(details
+        .into_iter()
+        .map(|maybe_details| match maybe_details {
+            Some(Details::Record(record_details)) => Some(record_details),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Latest revision of a mew and the time it was edited, if it has been
+fn get_latest_mew_for_details(
+    record_details: &RecordDetails,
+) -> ExternResult<(Mew, Option<Timestamp>)> {
+    match get_latest_mew_revision(&record_details.updates)? {
+        Some((latest_mew, latest_timestamp)) => Ok((latest_mew, Some(latest_timestamp))),
+        None => {
+            let mew: Mew = record_details
+                .record
                 .entry()
                 .to_app_option()
                 .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.into())))?
                 .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
                     "Malformed mew"
                 ))))?;
-            let my_pubkey = agent_info()?.agent_initial_pubkey;
-
-            let replies_count = count_responses_for_mew(CountResponsesForMewInput {
-                original_mew_hash: original_mew_hash.clone(),
-                response_type: Some(ResponseType::Reply),
-            })?;
-            let is_replied = get_response_for_mew_exists(GetResponseForMewExistsInput {
-                original_mew_hash: original_mew_hash.clone(),
-                response_type: Some(ResponseType::Reply),
-                response_author: my_pubkey.clone(),
-            })?;
-
-            let quotes_count = count_responses_for_mew(CountResponsesForMewInput {
-                original_mew_hash: original_mew_hash.clone(),
-                response_type: Some(ResponseType::Quote),
-            })?;
-            let is_quoted = get_response_for_mew_exists(GetResponseForMewExistsInput {
-                original_mew_hash: original_mew_hash.clone(),
-                response_type: Some(ResponseType::Quote),
-                response_author: my_pubkey.clone(),
-            })?;
-
-            let mewmews_count = count_responses_for_mew(CountResponsesForMewInput {
-                original_mew_hash: original_mew_hash.clone(),
-                response_type: Some(ResponseType::Mewmew),
-            })?;
-            let is_mewmewed = get_response_for_mew_exists(GetResponseForMewExistsInput {
-                original_mew_hash: original_mew_hash.clone(),
-                response_type: Some(ResponseType::Mewmew),
-                response_author: my_pubkey.clone(),
-            })?;
-
-            let licks_count = count_lickers_for_mew(original_mew_hash.clone())?;
-            let is_licked = is_licker_for_mew(IsLikerForHashInput {
-                liker: my_pubkey,
-                hash: original_mew_hash.into(),
-            })?;
-
-            let deleted_timestamp = deletes
-                .first()
-                .map(|first_delete| first_delete.action().timestamp());
-            let author_profile = get_agent_profile(record.action().author().clone())?;
-            let is_pinned = get_is_hash_pinned(record.action_hashed().hash.clone())?;
-
-            match mew.clone().mew_type {
-                MewType::Original => Ok(FeedMew {
-                    mew,
-                    action: record.action().clone(),
-                    action_hash: record.signed_action().as_hash().clone(),
-                    replies_count,
-                    quotes_count,
-                    licks_count,
-                    mewmews_count,
-                    deleted_timestamp,
-                    author_profile,
-                    is_pinned,
-                    is_licked,
-                    is_mewmewed,
-                    is_replied,
-                    is_quoted,
-                    original_mew: None,
-                }),
-                MewType::Reply(response_to_hash)
-                | MewType::Quote(response_to_hash)
-                | MewType::Mewmew(response_to_hash) => {
-                    let details = get_details(response_to_hash, GetOptions::default())?.ok_or(
-                        wasm_error!(WasmErrorInner::Guest(String::from("Mew not found"))),
-                    )?;
-
-                    match details {
-                        Details::Record(record_details) => {
-                            let original_mew_author_profile =
-                                get_agent_profile(record_details.record.action().author().clone())?;
-                            let original_mew_deleted_timestamp = record_details
-                                .deletes
-                                .first()
-                                .map(|first_delete| first_delete.action().timestamp());
-
-                            let original_mew: Mew = record_details
-                                .record
-                                .entry()
-                                .to_app_option()
-                                .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.into())))?
-                                .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
-                                    "Malformed original mew"
-                                ))))?;
-
-                            Ok(FeedMew {
-                                mew,
-                                action: record.action().clone(),
-                                action_hash: record.signed_action().as_hash().clone(),
-                                replies_count,
-                                quotes_count,
-                                licks_count,
-                                mewmews_count,
-                                author_profile,
-                                deleted_timestamp,
-                                is_pinned,
-                                is_licked,
-                                is_mewmewed,
-                                is_replied,
-                                is_quoted,
-                                original_mew: Some(EmbedMew {
-                                    mew: original_mew,
-                                    action: record_details.record.action().clone(),
-                                    action_hash: record_details.record.action_hashed().clone().hash,
-                                    author_profile: original_mew_author_profile,
-                                    deleted_timestamp: original_mew_deleted_timestamp,
-                                }),
-                            })
-                        }
-                        _ => Err(wasm_error!(WasmErrorInner::Guest(String::from(
-                            "Expected Details::Record, got something else"
-                        )))),
-                    }
-                }
-            }
+
+            Ok((mew, None))
         }
-        _ => Err(wasm_error!(WasmErrorInner::Guest(
-            "Expecting get_details to return record, got entry".into()
-        ))),
     }
 }
 
-#[hdk_extern]
-pub fn get_batch_mews_with_context(hashes: Vec<ActionHash>) -> ExternResult<Vec<FeedMew>> {
-    hashes
-        .into_iter()
-        .map(get_mew_with_context)
-        .collect::<ExternResult<Vec<FeedMew>>>()
+pub fn get_page_mews_with_context(
+    links_page: Page<Link>,
+    options: &FeedMewOptions,
+) -> ExternResult<Page<FeedMew>> {
+    let hashes: Vec<ActionHash> = links_page
+        .items
+        .iter()
+        .filter_map(|link| ActionHash::try_from(link.target.clone()).ok())
+        .collect();
+    let feed_mews = get_batch_mews_with_options(hashes, options)?;
+
+    Ok(links_page.with_items(feed_mews))
 }
 
 #[hdk_extern]
 pub fn get_responses_for_mew_with_context(
     input: GetResponsesForMewInput,
 ) -> ExternResult<Vec<FeedMew>> {
+    let options = input.options.clone().unwrap_or_default();
     let response_hashes = get_response_hashes_for_mew(input)?;
 
-    get_batch_mews_with_context(response_hashes)
+    get_batch_mews_with_options(response_hashes, &options)
 }
 
 fn get_agent_profile(agent_pub_key: AgentPubKey) -> ExternResult<Option<Profile>> {
@@ -0,0 +1,50 @@
+use hdk::prelude::*;
+use mews_integrity::*;
+
+/// The latest NotificationPreferences on my own source chain
+fn get_my_notification_preferences_record() -> ExternResult<Option<Record>> {
+    let filter = ChainQueryFilter::new()
+        .entry_type(UnitEntryTypes::NotificationPreferences.try_into()?)
+        .include_entries(true);
+
+    Ok(query(filter)?.pop())
+}
+
+/// My NotificationPreferences, with every notification enabled if I haven't set any
+#[hdk_extern]
+pub fn get_my_notification_preferences(_: ()) -> ExternResult<NotificationPreferences> {
+    match get_my_notification_preferences_record()? {
+        Some(record) => record
+            .entry()
+            .to_app_option()
+            .map_err(|e| wasm_error!(e))?
+            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                "Malformed NotificationPreferences"
+            )))),
+        None => Ok(NotificationPreferences::default()),
+    }
+}
+
+#[hdk_extern]
+pub fn set_my_notification_preferences(
+    notification_preferences: NotificationPreferences,
+) -> ExternResult<ActionHash> {
+    match get_my_notification_preferences_record()? {
+        Some(record) => update_entry(
+            record.signed_action().as_hash().clone(),
+            &notification_preferences,
+        ),
+        None => create_entry(EntryTypes::NotificationPreferences(
+            notification_preferences,
+        )),
+    }
+}
+
+pub fn is_notification_muted(
+    preferences: &NotificationPreferences,
+    notification_type: &NotificationType,
+    agent: &AgentPubKey,
+) -> bool {
+    preferences.disabled_types.contains(notification_type)
+        || preferences.muted_agents.contains(agent)
+}
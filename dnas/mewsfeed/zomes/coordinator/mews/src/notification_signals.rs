@@ -0,0 +1,69 @@
+use crate::agent_to_notifications::get_agent_profile;
+use crate::hidden_agents::get_my_hidden_agents;
+use crate::mew_with_context::get_mew_with_options;
+use crate::notification_preferences::{get_my_notification_preferences, is_notification_muted};
+use hdk::prelude::*;
+use mews_types::{FeedMewOptions, Notification, SendNotificationSignalInput};
+
+/// Push a notification about something I did to the agent it concerns
+#[hdk_extern]
+pub fn send_notification_signal(input: SendNotificationSignalInput) -> ExternResult<()> {
+    let my_pubkey = agent_info()?.agent_initial_pubkey;
+
+    // Viewer flags would describe me rather than the recipient, so they are left out
+    let feed_mew = match input.feed_mew_hash.clone() {
+        Some(hash) => Some(get_mew_with_options(
+            hash,
+            &FeedMewOptions {
+                include_viewer_flags: false,
+                ..FeedMewOptions::default()
+            },
+        )?),
+        None => None,
+    };
+    let recipient = match (input.recipient, &feed_mew) {
+        (Some(recipient), _) => recipient,
+        (None, Some(feed_mew)) => feed_mew.action.author().clone(),
+        (None, None) => {
+            return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+                "Notification signal needs a recipient or a feed mew"
+            ))))
+        }
+    };
+    if recipient == my_pubkey {
+        return Ok(());
+    }
+
+    let notification = Notification {
+        notification_type: input.notification_type,
+        timestamp: sys_time()?,
+        agent: my_pubkey.clone(),
+        agent_profile: get_agent_profile(my_pubkey)?,
+        feed_mew,
+    };
+
+    send_remote_signal(notification, vec![recipient])
+}
+
+/// Pass a notification pushed by another agent on to the UI
+#[hdk_extern]
+pub fn recv_remote_signal(notification: Notification) -> ExternResult<()> {
+    if call_info()?.provenance != notification.agent {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Notification signals can only be sent by the agent they are about"
+        ))));
+    }
+
+    let preferences = get_my_notification_preferences(())?;
+    if get_my_hidden_agents()?.contains(&notification.agent)
+        || is_notification_muted(
+            &preferences,
+            &notification.notification_type,
+            &notification.agent,
+        )
+    {
+        return Ok(());
+    }
+
+    emit_signal(notification)
+}
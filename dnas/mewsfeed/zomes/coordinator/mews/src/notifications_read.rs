@@ -0,0 +1,65 @@
+use hdk::prelude::*;
+use mews_integrity::*;
+
+/// The latest NotificationsRead on my own source chain
+fn get_my_notifications_read_record() -> ExternResult<Option<Record>> {
+    let filter = ChainQueryFilter::new()
+        .entry_type(UnitEntryTypes::NotificationsRead.try_into()?)
+        .include_entries(true);
+
+    Ok(query(filter)?.pop())
+}
+
+fn get_notifications_read_for_record(record: &Record) -> ExternResult<NotificationsRead> {
+    record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Malformed NotificationsRead"
+        ))))
+}
+
+pub fn get_my_last_read_timestamp() -> ExternResult<Option<Timestamp>> {
+    match get_my_notifications_read_record()? {
+        Some(record) => Ok(Some(
+            get_notifications_read_for_record(&record)?.last_read_timestamp,
+        )),
+        None => Ok(None),
+    }
+}
+
+#[hdk_extern]
+pub fn get_my_notifications_last_read(_: ()) -> ExternResult<Option<Timestamp>> {
+    get_my_last_read_timestamp()
+}
+
+/// Mark all my notifications up to and including the timestamp as read
+///
+/// The read cursor only moves forward, so the stored timestamp is returned.
+#[hdk_extern]
+pub fn mark_notifications_read(last_read_timestamp: Timestamp) -> ExternResult<Timestamp> {
+    let last_read_timestamp = last_read_timestamp.min(sys_time()?);
+    let notifications_read = NotificationsRead {
+        last_read_timestamp,
+    };
+
+    match get_my_notifications_read_record()? {
+        Some(record) => {
+            let previous = get_notifications_read_for_record(&record)?;
+            if previous.last_read_timestamp >= last_read_timestamp {
+                return Ok(previous.last_read_timestamp);
+            }
+
+            update_entry(
+                record.signed_action().as_hash().clone(),
+                &notifications_read,
+            )?;
+        }
+        None => {
+            create_entry(EntryTypes::NotificationsRead(notifications_read))?;
+        }
+    }
+
+    Ok(last_read_timestamp)
+}
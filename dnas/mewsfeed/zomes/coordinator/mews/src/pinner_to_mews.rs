@@ -1,17 +1,21 @@
-use crate::mew_with_context::get_batch_mews_with_context;
+use crate::mew_with_context::get_batch_mews_with_options;
 use hc_call_utils::call_local_zome;
 use hdk::prelude::*;
-use mews_types::FeedMew;
+use mews_types::{FeedMew, FeedMewOptions};
 
-pub fn get_is_hash_pinned(hash: ActionHash) -> ExternResult<bool> {
-    call_local_zome::<bool, ActionHash>("agent_pins", "is_hash_pinned", hash)
+pub fn get_my_pinned_hashes() -> ExternResult<Vec<AnyLinkableHash>> {
+    call_local_zome::<Vec<AnyLinkableHash>, AgentPubKey>(
+        "agent_pins",
+        "get_hashes_for_pinner",
+        agent_info()?.agent_initial_pubkey,
+    )
 }
 
 #[hdk_extern]
 pub fn get_mews_for_pinner_with_context(agent: AgentPubKey) -> ExternResult<Vec<FeedMew>> {
     let pinned_hashes = call_local_zome::<Vec<AnyLinkableHash>, AgentPubKey>(
         "agent_pins",
-        "get_hashes_for_pinner",
+        "get_pins_ordered",
         agent,
     )?;
     let pinned_action_hashes: Vec<ActionHash> = pinned_hashes
@@ -19,5 +23,5 @@ pub fn get_mews_for_pinner_with_context(agent: AgentPubKey) -> ExternResult<Vec<
         .filter_map(|h| ActionHash::try_from(h.clone()).ok())
         .collect();
 
-    get_batch_mews_with_context(pinned_action_hashes)
+    get_batch_mews_with_options(pinned_action_hashes, &FeedMewOptions::default())
 }
@@ -1,11 +1,6 @@
 use hdk::prelude::*;
 use mews_integrity::*;
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct SearchTagsInput {
-    pub query: String,
-    pub limit: usize,
-}
 #[hdk_extern]
 fn search_tags(input: SearchTagsInput) -> ExternResult<Vec<String>> {
     let prefix_index = make_tag_prefix_index()?;
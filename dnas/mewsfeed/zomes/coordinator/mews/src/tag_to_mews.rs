@@ -1,18 +1,28 @@
-use crate::mew_with_context::get_mew_with_context;
-use hc_link_pagination::{paginate_by_hash, HashPagination};
+use crate::hidden_agents::filter_hidden_links;
+use crate::mew_with_context::{get_mew_with_options, get_page_mews_with_context};
+use hc_link_pagination::{
+    paginate_by_cursor, paginate_by_hash, CursorPagination, HashPagination, Page,
+};
 use hdk::prelude::*;
 use mews_integrity::*;
 
-pub fn get_mew_hashes_for_tag(
+pub fn get_mew_links_for_tag(
     tag: String,
     link_type: impl LinkTypeFilterExt,
-    page: Option<HashPagination>,
-) -> ExternResult<Vec<ActionHash>> {
+) -> ExternResult<Vec<Link>> {
     let tag_text = make_tag_text(tag.clone());
     let prefix_index = make_tag_prefix_index()?;
     let result_path: Path = prefix_index.make_result_path(tag_text, Some(tag))?;
 
-    let links = get_links(result_path.path_entry_hash()?, link_type, None)?;
+    filter_hidden_links(get_links(result_path.path_entry_hash()?, link_type, None)?)
+}
+
+pub fn get_mew_hashes_for_tag(
+    tag: String,
+    link_type: impl LinkTypeFilterExt,
+    page: Option<HashPagination>,
+) -> ExternResult<Vec<ActionHash>> {
+    let links = get_mew_links_for_tag(tag, link_type)?;
     let links_page = paginate_by_hash(links, page)?;
     let hashes: Vec<ActionHash> = links_page
         .iter()
@@ -26,18 +36,31 @@ pub fn get_mews_for_tag_with_context(
     tag: String,
     link_type: impl LinkTypeFilterExt,
     page: Option<HashPagination>,
+    options: &FeedMewOptions,
 ) -> ExternResult<Vec<FeedMew>> {
     let hashes = get_mew_hashes_for_tag(tag, link_type, page)?;
 
     // Get mews with context
     let feedmews: Vec<FeedMew> = hashes
         .iter()
-        .filter_map(|h| get_mew_with_context(h.clone()).ok())
+        .filter_map(|h| get_mew_with_options(h.clone(), options).ok())
         .collect();
 
     Ok(feedmews)
 }
 
+pub fn get_mews_for_tag_page_with_context(
+    tag: String,
+    link_type: impl LinkTypeFilterExt,
+    page: Option<CursorPagination>,
+    options: &FeedMewOptions,
+) -> ExternResult<Page<FeedMew>> {
+    let links = get_mew_links_for_tag(tag, link_type)?;
+    let links_page = paginate_by_cursor(links, page)?;
+
+    get_page_mews_with_context(links_page, options)
+}
+
 pub fn make_tag_text(mut text: String) -> String {
     text.remove(0);
 
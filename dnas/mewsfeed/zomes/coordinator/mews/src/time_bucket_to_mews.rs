@@ -0,0 +1,147 @@
+use hc_link_pagination::{CursorPagination, Direction};
+use hdk::prelude::*;
+use mews_integrity::*;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+pub fn add_time_buckets_for_mew(
+    author: AgentPubKey,
+    mew_hash: ActionHash,
+    timestamp: Timestamp,
+) -> ExternResult<()> {
+    for root in [
+        make_all_mews_time_bucket_root(),
+        make_agent_mews_time_bucket_root(author),
+    ] {
+        let path = make_time_bucket_path(root, timestamp).typed(LinkTypes::TimeBucket)?;
+        path.ensure()?;
+        create_link(
+            path.path_entry_hash()?,
+            mew_hash.clone(),
+            LinkTypes::TimeBucketToMews,
+            (),
+        )?;
+    }
+
+    Ok(())
+}
+
+pub fn remove_time_buckets_for_mew(
+    author: AgentPubKey,
+    mew_hash: ActionHash,
+    timestamp: Timestamp,
+) -> ExternResult<()> {
+    for root in [
+        make_all_mews_time_bucket_root(),
+        make_agent_mews_time_bucket_root(author),
+    ] {
+        let path = make_time_bucket_path(root, timestamp);
+        let links = get_links(path.path_entry_hash()?, LinkTypes::TimeBucketToMews, None)?;
+        for link in links {
+            let action_hash =
+                ActionHash::try_from(link.target.clone()).map_err(|err| wasm_error!(err))?;
+            if action_hash.eq(&mew_hash) {
+                delete_link(link.create_link_hash)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Walk the time buckets under a root from newest to oldest, collecting mew links until one
+/// more than the page limit is found or the buckets run out.
+///
+/// Links authored by hidden agents are skipped while walking, so they don't count towards the page.
+pub fn get_mew_links_for_time_buckets(
+    root: Path,
+    page: &Option<CursorPagination>,
+    hidden_agents: &HashSet<AgentPubKey>,
+) -> ExternResult<Vec<Link>> {
+    let (before, limit) = match page {
+        Some(CursorPagination {
+            direction: Some(Direction::Ascending),
+            ..
+        }) => {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "Time bucketed feeds can only be paginated in descending order".into()
+            )))
+        }
+        Some(page) => (
+            page.after_cursor.as_ref().map(|cursor| cursor.timestamp),
+            page.limit,
+        ),
+        None => (None, usize::MAX),
+    };
+
+    let mut links: Vec<Link> = vec![];
+    collect_time_bucket_links(
+        root.typed(LinkTypes::TimeBucket)?,
+        vec![],
+        before,
+        limit,
+        hidden_agents,
+        &mut links,
+    )?;
+
+    Ok(links)
+}
+
+fn collect_time_bucket_links(
+    path: TypedPath,
+    bucket: Vec<u32>,
+    before: Option<Timestamp>,
+    limit: usize,
+    hidden_agents: &HashSet<AgentPubKey>,
+    links: &mut Vec<Link>,
+) -> ExternResult<()> {
+    if bucket.len() == TIME_BUCKET_DEPTH {
+        let bucket_links = get_links(path.path_entry_hash()?, LinkTypes::TimeBucketToMews, None)?;
+        links.extend(
+            bucket_links
+                .into_iter()
+                .filter(|link| !hidden_agents.contains(&link.author))
+                .filter(|link| match before {
+                    Some(before) => link.timestamp <= before,
+                    None => true,
+                }),
+        );
+
+        return Ok(());
+    }
+
+    let before_bucket = before.map(time_bucket_for_timestamp);
+    let mut children: Vec<(u32, TypedPath)> = path
+        .children_paths()?
+        .into_iter()
+        .filter_map(|child| {
+            let value = child
+                .leaf()
+                .and_then(|component| String::try_from(component).ok())
+                .and_then(|component| component.parse::<u32>().ok())?;
+
+            Some((value, child))
+        })
+        .collect();
+    children.sort_by_key(|(value, _)| Reverse(*value));
+
+    for (value, child) in children {
+        if links.len() > limit {
+            break;
+        }
+
+        let mut child_bucket = bucket.clone();
+        child_bucket.push(value);
+
+        // Skip buckets that only contain mews newer than the cursor
+        if let Some(before_bucket) = &before_bucket {
+            if child_bucket[..] > before_bucket[..child_bucket.len()] {
+                continue;
+            }
+        }
+
+        collect_time_bucket_links(child, child_bucket, before, limit, hidden_agents, links)?;
+    }
+
+    Ok(())
+}
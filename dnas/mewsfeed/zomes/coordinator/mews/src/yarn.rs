@@ -0,0 +1,118 @@
+use crate::hidden_agents::get_my_hidden_agents;
+use crate::mew::get_latest_mew;
+use crate::mew_to_responses::get_response_links_for_mew;
+use crate::mew_with_context::get_mew_with_options;
+use hc_link_pagination::{paginate_by_cursor, CursorPagination, Page};
+use hdk::prelude::*;
+use mews_integrity::*;
+use std::collections::HashSet;
+
+/// Number of reply levels returned below the root when no max_depth is given
+pub const DEFAULT_YARN_MAX_DEPTH: usize = 3;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetYarnInput {
+    pub mew_hash: ActionHash,
+    pub max_depth: Option<usize>,
+    pub replies_page: Option<CursorPagination>,
+}
+/// Get the whole yarn a mew belongs to, as a reply tree starting from its root mew
+///
+/// `replies_page` is applied to the replies of every node; its cursor only to the root's replies.
+#[hdk_extern]
+pub fn get_yarn(input: GetYarnInput) -> ExternResult<YarnNode> {
+    let root_hash = get_yarn_root_hash(input.mew_hash)?;
+
+    get_yarn_node(
+        root_hash,
+        input.max_depth.unwrap_or(DEFAULT_YARN_MAX_DEPTH),
+        input.replies_page,
+        &get_my_hidden_agents()?,
+    )
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetYarnRepliesInput {
+    pub mew_hash: ActionHash,
+    pub max_depth: Option<usize>,
+    pub page: Option<CursorPagination>,
+}
+/// Get the next page of replies below a node of a yarn, each with their own reply trees
+#[hdk_extern]
+pub fn get_yarn_replies(input: GetYarnRepliesInput) -> ExternResult<Page<YarnNode>> {
+    get_yarn_replies_page(
+        input.mew_hash,
+        input.max_depth.unwrap_or(DEFAULT_YARN_MAX_DEPTH),
+        input.page,
+        &get_my_hidden_agents()?,
+    )
+}
+
+/// Follow `MewType::Reply` ancestors until reaching a mew that is not a reply
+fn get_yarn_root_hash(mew_hash: ActionHash) -> ExternResult<ActionHash> {
+    let mut current_hash = mew_hash;
+    while let Some(Mew {
+        mew_type: MewType::Reply(parent_hash),
+        ..
+    }) = get_latest_mew(current_hash.clone())?
+    {
+        current_hash = parent_hash;
+    }
+
+    Ok(current_hash)
+}
+
+/// Hidden agents are looked up once for the whole tree and passed down to every node
+fn get_yarn_node(
+    mew_hash: ActionHash,
+    max_depth: usize,
+    replies_page: Option<CursorPagination>,
+    hidden_agents: &HashSet<AgentPubKey>,
+) -> ExternResult<YarnNode> {
+    let feed_mew = get_mew_with_options(mew_hash.clone(), &FeedMewOptions::default())?;
+    let deleted_timestamp = feed_mew.deleted_timestamp;
+
+    let replies = match max_depth {
+        0 => Page {
+            items: vec![],
+            next_cursor: None,
+            has_more: feed_mew.replies_count.unwrap_or(0) > 0,
+        },
+        _ => get_yarn_replies_page(mew_hash.clone(), max_depth - 1, replies_page, hidden_agents)?,
+    };
+
+    Ok(YarnNode {
+        action_hash: mew_hash,
+        feed_mew: match deleted_timestamp {
+            Some(_) => None,
+            None => Some(feed_mew),
+        },
+        deleted_timestamp,
+        replies,
+    })
+}
+
+fn get_yarn_replies_page(
+    mew_hash: ActionHash,
+    max_depth: usize,
+    page: Option<CursorPagination>,
+    hidden_agents: &HashSet<AgentPubKey>,
+) -> ExternResult<Page<YarnNode>> {
+    let mut links = get_response_links_for_mew(mew_hash, Some(ResponseType::Reply))?;
+    links.retain(|link| !hidden_agents.contains(&link.author));
+    let links_page = paginate_by_cursor(links, page.clone())?;
+
+    // Nested levels start from their first reply
+    let nested_page = page.map(|p| CursorPagination {
+        after_cursor: None,
+        ..p
+    });
+    let nodes = links_page
+        .items
+        .iter()
+        .filter_map(|link| ActionHash::try_from(link.target.clone()).ok())
+        .map(|reply_hash| get_yarn_node(reply_hash, max_depth, nested_page.clone(), hidden_agents))
+        .collect::<ExternResult<Vec<YarnNode>>>()?;
+
+    Ok(links_page.with_items(nodes))
+}
@@ -1,5 +1,7 @@
+pub mod pin_order;
 pub mod pinner_to_hashes;
 use hdi::prelude::*;
+pub use pin_order::*;
 pub use pinner_to_hashes::*;
 #[derive(Serialize, Deserialize)]
 #[hdk_link_types]
@@ -0,0 +1,34 @@
+use hdi::prelude::*;
+
+/// Tag of a PinnerToHashes link, placing the pin in the pinner's chosen order
+///
+/// New links must have one. Empty tags are only accepted on links made before ordering, which
+/// are shown after the ordered ones.
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq, Eq)]
+pub struct PinTag {
+    /// Lower positions are shown first
+    pub position: u32,
+}
+
+impl TryFrom<LinkTag> for PinTag {
+    type Error = WasmError;
+
+    fn try_from(tag: LinkTag) -> Result<Self, Self::Error> {
+        PinTag::try_from(SerializedBytes::from(UnsafeBytes::from(tag.into_inner())))
+            .map_err(|err| wasm_error!(err))
+    }
+}
+
+/// The part of the DNA properties read by the agent_pins zome
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+struct AgentPinsDnaProperties {
+    max_pins: Option<usize>,
+}
+
+/// The most mews an agent can have pinned at once, unlimited when not set in DNA properties
+pub fn get_max_pins() -> ExternResult<Option<usize>> {
+    let properties = AgentPinsDnaProperties::try_from(dna_info()?.modifiers.properties)
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.into())))?;
+
+    Ok(properties.max_pins)
+}
@@ -1,15 +1,40 @@
+use crate::{get_max_pins, LinkTypes, PinTag};
+use hc_active_links::{get_active_links, has_active_link};
 use hdi::prelude::*;
+
 pub fn validate_create_link_pinner_to_hashes(
     action: CreateLink,
     base_address: AnyLinkableHash,
-    _target_address: AnyLinkableHash,
-    _tag: LinkTag,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
-    if base_address != AnyLinkableHash::from(action.author) {
+    if base_address != AnyLinkableHash::from(action.author.clone()) {
         return Ok(ValidateCallbackResult::Invalid(
             "You cannot change what others pnin".into(),
         ));
     }
+    if PinTag::try_from(tag).is_err() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Malformed PinnerToHashes tag".into(),
+        ));
+    }
+
+    let active_pin_links = get_active_links(&action, LinkTypes::PinnerToHashes, |_| true)?;
+    if active_pin_links.iter().any(|create_link| {
+        create_link.base_address == base_address && create_link.target_address == target_address
+    }) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "You already pinned this".into(),
+        ));
+    }
+    if let Some(max_pins) = get_max_pins()? {
+        if active_pin_links.len() >= max_pins {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "You can pin at most {} mews",
+                max_pins
+            )));
+        }
+    }
 
     Ok(ValidateCallbackResult::Valid)
 }
@@ -30,16 +55,24 @@ pub fn validate_delete_link_pinner_to_hashes(
 }
 pub fn validate_create_link_hash_to_pinners(
     action: CreateLink,
-    _base_address: AnyLinkableHash,
+    base_address: AnyLinkableHash,
     target_address: AnyLinkableHash,
     _tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
-    if target_address != AnyLinkableHash::from(action.author) {
+    if target_address != AnyLinkableHash::from(action.author.clone()) {
         return Ok(ValidateCallbackResult::Invalid(
             "You cannot change what others pnin".into(),
         ));
     }
 
+    if has_active_link(&action, LinkTypes::HashToPinners, |create_link| {
+        create_link.base_address == base_address && create_link.target_address == target_address
+    })? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "You already pinned this".into(),
+        ));
+    }
+
     Ok(ValidateCallbackResult::Valid)
 }
 pub fn validate_delete_link_hash_to_pinners(
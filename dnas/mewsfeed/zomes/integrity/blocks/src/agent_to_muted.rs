@@ -0,0 +1,40 @@
+use hdi::prelude::*;
+pub fn validate_create_link_agent_to_muted(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if base_address != AnyLinkableHash::from(action.author.clone()) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "You cannot change who others mute".into(),
+        ));
+    }
+    if AgentPubKey::try_from(target_address.clone()).is_err() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "You can only mute agents".into(),
+        ));
+    }
+    if target_address == AnyLinkableHash::from(action.author) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "You cannot mute yourself".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_link_agent_to_muted(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != original_action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "You cannot change who others unmute".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
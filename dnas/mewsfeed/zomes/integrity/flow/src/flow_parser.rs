@@ -0,0 +1,239 @@
+use chrono::{NaiveDate, TimeZone, Utc};
+use hdi::prelude::*;
+
+/// The kind of flow a mew starts with, written as `%Request:`, `%Promise:` or `%Thanks:`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum FlowKind {
+    Request,
+    Promise,
+    Thanks,
+}
+
+/// An amount written as `$<value><CURRENCY>`, such as `$1.5BTC`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FlowAmount {
+    /// The decimal value as written, to avoid rounding it
+    pub value: String,
+    pub currency: String,
+}
+
+/// Dates written as `between YYYY/MM/DD-YYYY/MM/DD`, both included
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FlowDateRange {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl FlowDateRange {
+    /// The start of the day after the end date in UTC, when the range is over
+    pub fn deadline(&self) -> Option<Timestamp> {
+        let day_after_end = self.end.succ_opt()?.and_hms_opt(0, 0, 0)?;
+
+        Some(Timestamp::from_micros(
+            Utc.from_utc_datetime(&day_after_end).timestamp_micros(),
+        ))
+    }
+}
+
+/// A flow mew parsed into its parts
+///
+/// `%Request: @username $1BTC between 2023/05/01-2023/05/06 Make me a coffee`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FlowRequest {
+    pub kind: FlowKind,
+    /// The mentioned agent as written, such as `@username`
+    pub mention: Option<String>,
+    pub amount: Option<FlowAmount>,
+    pub between: Option<FlowDateRange>,
+    /// Free text after the other parts, empty if there is none
+    pub text: String,
+}
+
+/// Why a flow mew couldn't be parsed, and where
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FlowParseError {
+    /// Character offset into the mew text where parsing failed
+    pub position: usize,
+    pub message: String,
+}
+
+/// Whether a mew is meant as a flow, which is when it starts with `%`
+pub fn is_flow_text(text: &str) -> bool {
+    text.starts_with('%')
+}
+
+pub fn parse_flow(text: &str) -> Result<FlowRequest, FlowParseError> {
+    let mut parser = FlowParser::new(text);
+
+    parser.expect_char('%', "A flow must start with %")?;
+    let kind_position = parser.position;
+    let kind = match parser.take_while(|c| c.is_ascii_alphabetic()).as_str() {
+        "Request" => FlowKind::Request,
+        "Promise" => FlowKind::Promise,
+        "Thanks" => FlowKind::Thanks,
+        _ => {
+            return Err(
+                parser.error_at(kind_position, "Expected Request, Promise or Thanks after %")
+            )
+        }
+    };
+    parser.expect_char(':', "Expected : after the flow kind")?;
+    parser.skip_spaces();
+
+    let mention = match parser.peek() {
+        Some('@') => Some(parser.parse_mention()?),
+        _ => None,
+    };
+    parser.skip_spaces();
+
+    let amount = match parser.peek() {
+        Some('$') => Some(parser.parse_amount()?),
+        _ => None,
+    };
+    parser.skip_spaces();
+
+    let between = match parser.starts_with("between") {
+        true => Some(parser.parse_between()?),
+        false => None,
+    };
+    parser.skip_spaces();
+
+    Ok(FlowRequest {
+        kind,
+        mention,
+        amount,
+        between,
+        text: parser.rest().trim_end().to_string(),
+    })
+}
+
+struct FlowParser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl FlowParser {
+    fn new(text: &str) -> Self {
+        FlowParser {
+            // Non-breaking spaces from rich text inputs are plain spaces here
+            chars: text.replace('\u{00A0}', " ").chars().collect(),
+            position: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn rest(&self) -> String {
+        self.chars[self.position..].iter().collect()
+    }
+
+    fn starts_with(&self, prefix: &str) -> bool {
+        self.rest().starts_with(prefix)
+    }
+
+    fn error_at(&self, position: usize, message: &str) -> FlowParseError {
+        FlowParseError {
+            position,
+            message: message.to_string(),
+        }
+    }
+
+    fn expect_char(&mut self, expected: char, message: &str) -> Result<(), FlowParseError> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.position += 1;
+                Ok(())
+            }
+            _ => Err(self.error_at(self.position, message)),
+        }
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+        let start = self.position;
+        while self.peek().map_or(false, &predicate) {
+            self.position += 1;
+        }
+
+        self.chars[start..self.position].iter().collect()
+    }
+
+    fn skip_spaces(&mut self) {
+        self.take_while(|c| c == ' ');
+    }
+
+    fn parse_mention(&mut self) -> Result<String, FlowParseError> {
+        let start = self.position;
+        self.expect_char('@', "Expected @ before the mentioned agent")?;
+        let name = self.take_while(|c| !c.is_whitespace());
+        if name.is_empty() {
+            return Err(self.error_at(start + 1, "Expected an agent name after @"));
+        }
+
+        Ok(format!("@{}", name))
+    }
+
+    fn parse_amount(&mut self) -> Result<FlowAmount, FlowParseError> {
+        self.expect_char('$', "Expected $ before the amount")?;
+        let value_position = self.position;
+        let value = self.take_while(|c| c.is_ascii_digit() || c == '.');
+        let is_valid_value = !value.is_empty()
+            && value.matches('.').count() <= 1
+            && !value.starts_with('.')
+            && !value.ends_with('.');
+        if !is_valid_value {
+            return Err(self.error_at(value_position, "Expected a number after $, such as $1.5"));
+        }
+
+        let currency_position = self.position;
+        let currency = self.take_while(|c| c.is_ascii_uppercase());
+        if currency.is_empty() {
+            return Err(self.error_at(
+                currency_position,
+                "Expected an uppercase currency after the amount, such as BTC",
+            ));
+        }
+
+        Ok(FlowAmount { value, currency })
+    }
+
+    fn parse_between(&mut self) -> Result<FlowDateRange, FlowParseError> {
+        self.position += "between".len();
+        self.skip_spaces();
+        let start_position = self.position;
+        let start = self.parse_date()?;
+        self.expect_char('-', "Expected - between the start and end dates")?;
+        let end = self.parse_date()?;
+        if end < start {
+            return Err(self.error_at(start_position, "The end date is before the start date"));
+        }
+
+        Ok(FlowDateRange { start, end })
+    }
+
+    /// A `YYYY/MM/DD` date
+    fn parse_date(&mut self) -> Result<NaiveDate, FlowParseError> {
+        let date_position = self.position;
+        let year = self.parse_number(4)?;
+        self.expect_char('/', "Expected a date such as 2023/05/01")?;
+        let month = self.parse_number(2)?;
+        self.expect_char('/', "Expected a date such as 2023/05/01")?;
+        let day = self.parse_number(2)?;
+
+        NaiveDate::from_ymd_opt(year as i32, month, day)
+            .ok_or(self.error_at(date_position, "This date doesn't exist"))
+    }
+
+    fn parse_number(&mut self, digits: usize) -> Result<u32, FlowParseError> {
+        let number_position = self.position;
+        let number = self.take_while(|c| c.is_ascii_digit());
+        if number.len() != digits {
+            return Err(self.error_at(number_position, "Expected a date such as 2023/05/01"));
+        }
+
+        number
+            .parse()
+            .map_err(|_| self.error_at(number_position, "Expected a date such as 2023/05/01"))
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{ContractStatus, LinkTypes, MewContract};
+use hc_active_links::has_active_link;
+use hdi::prelude::*;
+
+/// The requestor thanking a promiser links the Thanked contract from the promiser
+pub fn validate_create_link_jobs_done(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash =
+        ActionHash::try_from(target_address.clone()).map_err(|err| wasm_error!(err))?;
+    let record = must_get_valid_record(action_hash)?;
+    let mew_contract: MewContract = match record.entry().to_app_option() {
+        Ok(Some(mew_contract)) => mew_contract,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Linked action must reference a MewContract".into(),
+            ))
+        }
+    };
+    if mew_contract.status != ContractStatus::Thanked {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Jobs done must be Thanked contracts".into(),
+        ));
+    }
+    if mew_contract.requestor != action.author || *record.action().author() != action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the requestor who thanked a contract can record it as a job done".into(),
+        ));
+    }
+    if base_address != AnyLinkableHash::from(mew_contract.promiser) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Jobs done must be linked from their promiser".into(),
+        ));
+    }
+    if has_active_link(&action, LinkTypes::JobsDone, |create_link| {
+        create_link.base_address == base_address && create_link.target_address == target_address
+    })? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "This job done is already recorded".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_delete_link_jobs_done(
+    _action: DeleteLink,
+    _original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(
+        "Jobs done cannot be removed".into(),
+    ))
+}
@@ -1,216 +1,342 @@
 pub mod flow;
 pub use flow::*;
+pub mod flow_parser;
+pub use flow_parser::*;
+pub mod jobs_done;
+pub use jobs_done::*;
+pub mod mew_contract;
 use hdi::prelude::*;
+pub use mew_contract::*;
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+#[hdk_entry_defs]
+#[unit_enum(UnitEntryTypes)]
+pub enum EntryTypes {
+    MewContract(MewContract),
+}
 
 #[derive(Serialize, Deserialize)]
 #[hdk_link_types]
 pub enum LinkTypes {
     JobsDone,
-}
-
-#[hdk_entry_helper]
-#[derive(Clone, PartialEq, Eq)]
-pub struct MewContract {
-    requestor: AgentPubKey,
-    promiser: AgentPubKey,
-    response_mew: String,
+    MewToContracts,
+    PromiserToContracts,
 }
 
 #[hdk_extern]
 pub fn genesis_self_check(_data: GenesisSelfCheckData) -> ExternResult<ValidateCallbackResult> {
     Ok(ValidateCallbackResult::Valid)
 }
-
+pub fn validate_agent_joining(
+    _agent_pub_key: AgentPubKey,
+    _membrane_proof: &Option<MembraneProof>,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Valid)
+}
 #[allow(unused_variables)]
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
-    Ok(ValidateCallbackResult::Valid)
+    match op.flattened::<EntryTypes, LinkTypes>()? {
+        FlatOp::StoreEntry(store_entry) => match store_entry {
+            OpEntry::CreateEntry { app_entry, action } => {
+                validate_create_app_entry(EntryCreationAction::Create(action), app_entry)
+            }
+            OpEntry::UpdateEntry {
+                original_action_hash,
+                app_entry,
+                action,
+                ..
+            } => validate_update_app_entry(action, app_entry, original_action_hash),
+            _ => Ok(ValidateCallbackResult::Valid),
+        },
+        FlatOp::RegisterUpdate(update_entry) => match update_entry {
+            OpUpdate::Entry {
+                original_action,
+                original_app_entry,
+                app_entry,
+                action,
+            } => match (app_entry, original_app_entry) {
+                (
+                    EntryTypes::MewContract(mew_contract),
+                    EntryTypes::MewContract(original_mew_contract),
+                ) => validate_update_mew_contract(
+                    action,
+                    mew_contract,
+                    original_action,
+                    original_mew_contract,
+                ),
+            },
+            _ => Ok(ValidateCallbackResult::Valid),
+        },
+        FlatOp::RegisterDelete(delete_entry) => match delete_entry {
+            OpDelete::Entry {
+                original_action,
+                original_app_entry,
+                action,
+            } => validate_delete_app_entry(action, original_action, original_app_entry),
+            _ => Ok(ValidateCallbackResult::Valid),
+        },
+        FlatOp::RegisterCreateLink {
+            link_type,
+            base_address,
+            target_address,
+            tag,
+            action,
+        } => match link_type {
+            LinkTypes::JobsDone => {
+                validate_create_link_jobs_done(action, base_address, target_address, tag)
+            }
+            LinkTypes::MewToContracts => {
+                validate_create_link_mew_to_contracts(action, base_address, target_address, tag)
+            }
+            LinkTypes::PromiserToContracts => validate_create_link_promiser_to_contracts(
+                action,
+                base_address,
+                target_address,
+                tag,
+            ),
+        },
+        FlatOp::RegisterDeleteLink {
+            link_type,
+            base_address,
+            target_address,
+            tag,
+            original_action,
+            action,
+        } => match link_type {
+            LinkTypes::JobsDone => validate_delete_link_jobs_done(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::MewToContracts => validate_delete_link_mew_to_contracts(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::PromiserToContracts => validate_delete_link_promiser_to_contracts(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+        },
+        FlatOp::StoreRecord(store_record) => match store_record {
+            OpRecord::CreateEntry { app_entry, action } => {
+                validate_create_app_entry(EntryCreationAction::Create(action), app_entry)
+            }
+            OpRecord::UpdateEntry {
+                original_action_hash,
+                app_entry,
+                action,
+                ..
+            } => validate_update_app_entry(action, app_entry, original_action_hash),
+            OpRecord::DeleteEntry {
+                original_action_hash,
+                action,
+                ..
+            } => {
+                let original_record = must_get_valid_record(original_action_hash)?;
+                let original_action = match original_record.action().clone() {
+                    Action::Create(create) => EntryCreationAction::Create(create),
+                    Action::Update(update) => EntryCreationAction::Update(update),
+                    _ => {
+                        return Ok(ValidateCallbackResult::Invalid(
+                            "Original action for a delete must be a Create or Update action"
+                                .to_string(),
+                        ));
+                    }
+                };
+                let app_entry_type = match original_action.entry_type() {
+                    EntryType::App(app_entry_type) => app_entry_type,
+                    _ => {
+                        return Ok(ValidateCallbackResult::Valid);
+                    }
+                };
+                let entry = match original_record.entry().as_option() {
+                    Some(entry) => entry,
+                    None => {
+                        return Ok(ValidateCallbackResult::Invalid(
+                            "Original record for a delete of a public entry must contain an entry"
+                                .to_string(),
+                        ));
+                    }
+                };
+                let original_app_entry = match EntryTypes::deserialize_from_type(
+                    app_entry_type.zome_index,
+                    app_entry_type.entry_index,
+                    entry,
+                )? {
+                    Some(app_entry) => app_entry,
+                    None => {
+                        return Ok(ValidateCallbackResult::Invalid(
+                            "Original app entry must be one of the defined entry types for this zome"
+                                .to_string(),
+                        ));
+                    }
+                };
+                validate_delete_app_entry(action, original_action, original_app_entry)
+            }
+            OpRecord::CreateLink {
+                base_address,
+                target_address,
+                tag,
+                link_type,
+                action,
+            } => match link_type {
+                LinkTypes::JobsDone => {
+                    validate_create_link_jobs_done(action, base_address, target_address, tag)
+                }
+                LinkTypes::MewToContracts => {
+                    validate_create_link_mew_to_contracts(action, base_address, target_address, tag)
+                }
+                LinkTypes::PromiserToContracts => validate_create_link_promiser_to_contracts(
+                    action,
+                    base_address,
+                    target_address,
+                    tag,
+                ),
+            },
+            OpRecord::DeleteLink {
+                original_action_hash,
+                base_address,
+                action,
+            } => {
+                let record = must_get_valid_record(original_action_hash)?;
+                let create_link = match record.action() {
+                    Action::CreateLink(create_link) => create_link.clone(),
+                    _ => {
+                        return Ok(ValidateCallbackResult::Invalid(
+                            "The action that a DeleteLink deletes must be a CreateLink".to_string(),
+                        ));
+                    }
+                };
+                let link_type =
+                    match LinkTypes::from_type(create_link.zome_index, create_link.link_type)? {
+                        Some(lt) => lt,
+                        None => {
+                            return Ok(ValidateCallbackResult::Valid);
+                        }
+                    };
+                match link_type {
+                    LinkTypes::JobsDone => validate_delete_link_jobs_done(
+                        action,
+                        create_link.clone(),
+                        base_address,
+                        create_link.target_address,
+                        create_link.tag,
+                    ),
+                    LinkTypes::MewToContracts => validate_delete_link_mew_to_contracts(
+                        action,
+                        create_link.clone(),
+                        base_address,
+                        create_link.target_address,
+                        create_link.tag,
+                    ),
+                    LinkTypes::PromiserToContracts => validate_delete_link_promiser_to_contracts(
+                        action,
+                        create_link.clone(),
+                        base_address,
+                        create_link.target_address,
+                        create_link.tag,
+                    ),
+                }
+            }
+            OpRecord::CreatePrivateEntry { .. } => Ok(ValidateCallbackResult::Valid),
+            OpRecord::UpdatePrivateEntry { .. } => Ok(ValidateCallbackResult::Valid),
+            OpRecord::CreateCapClaim { .. } => Ok(ValidateCallbackResult::Valid),
+            OpRecord::CreateCapGrant { .. } => Ok(ValidateCallbackResult::Valid),
+            OpRecord::UpdateCapClaim { .. } => Ok(ValidateCallbackResult::Valid),
+            OpRecord::UpdateCapGrant { .. } => Ok(ValidateCallbackResult::Valid),
+            OpRecord::Dna { .. } => Ok(ValidateCallbackResult::Valid),
+            OpRecord::OpenChain { .. } => Ok(ValidateCallbackResult::Valid),
+            OpRecord::CloseChain { .. } => Ok(ValidateCallbackResult::Valid),
+            OpRecord::InitZomesComplete { .. } => Ok(ValidateCallbackResult::Valid),
+            _ => Ok(ValidateCallbackResult::Valid),
+        },
+        FlatOp::RegisterAgentActivity(agent_activity) => match agent_activity {
+            OpActivity::CreateAgent { agent, action } => {
+                let previous_action = must_get_action(action.prev_action)?;
+                match previous_action.action() {
+                        Action::AgentValidationPkg(
+                            AgentValidationPkg { membrane_proof, .. },
+                        ) => validate_agent_joining(agent, membrane_proof),
+                        _ => {
+                            Ok(
+                                ValidateCallbackResult::Invalid(
+                                    "The previous action for a `CreateAgent` action must be an `AgentValidationPkg`"
+                                        .to_string(),
+                                ),
+                            )
+                        }
+                    }
+            }
+            _ => Ok(ValidateCallbackResult::Valid),
+        },
+    }
+}
+
+fn validate_create_app_entry(
+    action: EntryCreationAction,
+    app_entry: EntryTypes,
+) -> ExternResult<ValidateCallbackResult> {
+    match app_entry {
+        EntryTypes::MewContract(mew_contract) => validate_create_mew_contract(action, mew_contract),
+    }
 }
 
-// #[derive(Serialize, Deserialize)]
-// #[hdk_link_types]
-// pub enum LinkTypes {
-//     FollowerToCreators,
-//     CreatorToFollowers,
-// }
-// #[hdk_extern]
-// pub fn genesis_self_check(_data: GenesisSelfCheckData) -> ExternResult<ValidateCallbackResult> {
-//     Ok(ValidateCallbackResult::Valid)
-// }
-// pub fn validate_agent_joining(
-//     _agent_pub_key: AgentPubKey,
-//     _membrane_proof: &Option<MembraneProof>,
-// ) -> ExternResult<ValidateCallbackResult> {
-//     Ok(ValidateCallbackResult::Valid)
-// }
-// #[allow(unused_variables)]
-// #[hdk_extern]
-// pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
-//     match op.flattened::<(), LinkTypes>()? {
-//         FlatOp::StoreEntry(store_entry) => match store_entry {
-//             OpEntry::CreateEntry { app_entry, action } => Ok(ValidateCallbackResult::Valid),
-//             OpEntry::UpdateEntry {
-//                 app_entry, action, ..
-//             } => Ok(ValidateCallbackResult::Valid),
-//             _ => Ok(ValidateCallbackResult::Valid),
-//         },
-//         FlatOp::RegisterUpdate(update_entry) => match update_entry {
-//             OpUpdate::Entry {
-//                 original_action,
-//                 original_app_entry,
-//                 app_entry,
-//                 action,
-//             } => Ok(ValidateCallbackResult::Invalid(
-//                 "There are no entry types in this integrity zome".to_string(),
-//             )),
-//             _ => Ok(ValidateCallbackResult::Valid),
-//         },
-//         FlatOp::RegisterDelete(delete_entry) => match delete_entry {
-//             OpDelete::Entry {
-//                 original_action,
-//                 original_app_entry,
-//                 action,
-//             } => Ok(ValidateCallbackResult::Valid),
-//             _ => Ok(ValidateCallbackResult::Valid),
-//         },
-//         FlatOp::RegisterCreateLink {
-//             link_type,
-//             base_address,
-//             target_address,
-//             tag,
-//             action,
-//         } => match link_type {
-//             LinkTypes::FollowerToCreators => {
-//                 validate_create_link_follower_to_creators(action, base_address, target_address, tag)
-//             }
-//             LinkTypes::CreatorToFollowers => {
-//                 validate_create_link_creator_to_followers(action, base_address, target_address, tag)
-//             }
-//         },
-//         FlatOp::RegisterDeleteLink {
-//             link_type,
-//             base_address,
-//             target_address,
-//             tag,
-//             original_action,
-//             action,
-//         } => match link_type {
-//             LinkTypes::FollowerToCreators => validate_delete_link_follower_to_creators(
-//                 action,
-//                 original_action,
-//                 base_address,
-//                 target_address,
-//                 tag,
-//             ),
-//             LinkTypes::CreatorToFollowers => validate_delete_link_creator_to_followers(
-//                 action,
-//                 original_action,
-//                 base_address,
-//                 target_address,
-//                 tag,
-//             ),
-//         },
-//         FlatOp::StoreRecord(store_record) => match store_record {
-//             OpRecord::CreateEntry { app_entry, action } => Ok(ValidateCallbackResult::Valid),
-//             OpRecord::UpdateEntry {
-//                 original_action_hash,
-//                 app_entry,
-//                 action,
-//                 ..
-//             } => Ok(ValidateCallbackResult::Valid),
-//             OpRecord::DeleteEntry {
-//                 original_action_hash,
-//                 action,
-//                 ..
-//             } => Ok(ValidateCallbackResult::Valid),
-//             OpRecord::CreateLink {
-//                 base_address,
-//                 target_address,
-//                 tag,
-//                 link_type,
-//                 action,
-//             } => match link_type {
-//                 LinkTypes::FollowerToCreators => validate_create_link_follower_to_creators(
-//                     action,
-//                     base_address,
-//                     target_address,
-//                     tag,
-//                 ),
-//                 LinkTypes::CreatorToFollowers => validate_create_link_creator_to_followers(
-//                     action,
-//                     base_address,
-//                     target_address,
-//                     tag,
-//                 ),
-//             },
-//             OpRecord::DeleteLink {
-//                 original_action_hash,
-//                 base_address,
-//                 action,
-//             } => {
-//                 let record = must_get_valid_record(original_action_hash)?;
-//                 let create_link = match record.action() {
-//                     Action::CreateLink(create_link) => create_link.clone(),
-//                     _ => {
-//                         return Ok(ValidateCallbackResult::Invalid(
-//                             "The action that a DeleteLink deletes must be a CreateLink".to_string(),
-//                         ));
-//                     }
-//                 };
-//                 let link_type =
-//                     match LinkTypes::from_type(create_link.zome_index, create_link.link_type)? {
-//                         Some(lt) => lt,
-//                         None => {
-//                             return Ok(ValidateCallbackResult::Valid);
-//                         }
-//                     };
-//                 match link_type {
-//                     LinkTypes::FollowerToCreators => validate_delete_link_follower_to_creators(
-//                         action,
-//                         create_link.clone(),
-//                         base_address,
-//                         create_link.target_address,
-//                         create_link.tag,
-//                     ),
-//                     LinkTypes::CreatorToFollowers => validate_delete_link_creator_to_followers(
-//                         action,
-//                         create_link.clone(),
-//                         base_address,
-//                         create_link.target_address,
-//                         create_link.tag,
-//                     ),
-//                 }
-//             }
-//             OpRecord::CreatePrivateEntry { .. } => Ok(ValidateCallbackResult::Valid),
-//             OpRecord::UpdatePrivateEntry { .. } => Ok(ValidateCallbackResult::Valid),
-//             OpRecord::CreateCapClaim { .. } => Ok(ValidateCallbackResult::Valid),
-//             OpRecord::CreateCapGrant { .. } => Ok(ValidateCallbackResult::Valid),
-//             OpRecord::UpdateCapClaim { .. } => Ok(ValidateCallbackResult::Valid),
-//             OpRecord::UpdateCapGrant { .. } => Ok(ValidateCallbackResult::Valid),
-//             OpRecord::Dna { .. } => Ok(ValidateCallbackResult::Valid),
-//             OpRecord::OpenChain { .. } => Ok(ValidateCallbackResult::Valid),
-//             OpRecord::CloseChain { .. } => Ok(ValidateCallbackResult::Valid),
-//             OpRecord::InitZomesComplete { .. } => Ok(ValidateCallbackResult::Valid),
-//             _ => Ok(ValidateCallbackResult::Valid),
-//         },
-//         FlatOp::RegisterAgentActivity(agent_activity) => match agent_activity {
-//             OpActivity::CreateAgent { agent, action } => {
-//                 let previous_action = must_get_action(action.prev_action)?;
-//                 match previous_action.action() {
-//                         Action::AgentValidationPkg(
-//                             AgentValidationPkg { membrane_proof, .. },
-//                         ) => validate_agent_joining(agent, membrane_proof),
-//                         _ => {
-//                             Ok(
-//                                 ValidateCallbackResult::Invalid(
-//                                     "The previous action for a `CreateAgent` action must be an `AgentValidationPkg`"
-//                                         .to_string(),
-//                                 ),
-//                             )
-//                         }
-//                     }
-//             }
-//             _ => Ok(ValidateCallbackResult::Valid),
-//         },
-//     }
-// }
+fn validate_delete_app_entry(
+    action: Delete,
+    original_action: EntryCreationAction,
+    original_app_entry: EntryTypes,
+) -> ExternResult<ValidateCallbackResult> {
+    match original_app_entry {
+        EntryTypes::MewContract(original_mew_contract) => {
+            validate_delete_mew_contract(action, original_action, original_mew_contract)
+        }
+    }
+}
+
+/// Updates are validated against the revision they update, which is fetched here
+fn validate_update_app_entry(
+    action: Update,
+    app_entry: EntryTypes,
+    original_action_hash: ActionHash,
+) -> ExternResult<ValidateCallbackResult> {
+    let original_record = must_get_valid_record(original_action_hash)?;
+    let original_action = match original_record.action().clone() {
+        Action::Create(create) => EntryCreationAction::Create(create),
+        Action::Update(update) => EntryCreationAction::Update(update),
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Original action for an update must be a Create or Update action".to_string(),
+            ));
+        }
+    };
+    match app_entry {
+        EntryTypes::MewContract(mew_contract) => {
+            let original_mew_contract: Option<MewContract> = original_record
+                .entry()
+                .to_app_option()
+                .map_err(|e| wasm_error!(e))?;
+            match original_mew_contract {
+                Some(original_mew_contract) => validate_update_mew_contract(
+                    action,
+                    mew_contract,
+                    original_action,
+                    original_mew_contract,
+                ),
+                None => Ok(ValidateCallbackResult::Invalid(
+                    "The updated entry type must be the same as the original entry type"
+                        .to_string(),
+                )),
+            }
+        }
+    }
+}
@@ -0,0 +1,397 @@
+use crate::{parse_flow, FlowAmount, FlowKind, FlowRequest};
+use hdi::prelude::*;
+use mews_types::{Mew, MewType};
+
+/// Where a contract is in its lifecycle
+///
+/// Requested -> Promised -> Delivered -> Thanked, and until delivered it can be Cancelled or Expire.
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq, Eq)]
+pub enum ContractStatus {
+    /// The requestor asked a mentioned agent, who hasn't promised yet
+    Requested,
+    Promised,
+    Delivered,
+    Thanked,
+    Cancelled,
+    /// The deadline passed before the contract was delivered
+    Expired,
+}
+
+impl ContractStatus {
+    /// The statuses a contract in this status can move to
+    pub fn next_statuses(&self) -> Vec<ContractStatus> {
+        match self {
+            ContractStatus::Requested => vec![
+                ContractStatus::Promised,
+                ContractStatus::Cancelled,
+                ContractStatus::Expired,
+            ],
+            ContractStatus::Promised => vec![
+                ContractStatus::Delivered,
+                ContractStatus::Cancelled,
+                ContractStatus::Expired,
+            ],
+            ContractStatus::Delivered => vec![ContractStatus::Thanked],
+            ContractStatus::Thanked | ContractStatus::Cancelled | ContractStatus::Expired => {
+                vec![]
+            }
+        }
+    }
+}
+
+/// A contract between a requestor's %Request and the agent promising to fulfil it
+///
+/// Each status change is an update of the previous revision.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct MewContract {
+    pub requestor: AgentPubKey,
+    pub promiser: AgentPubKey,
+    /// The requestor's %Request mew
+    pub request_mew: ActionHash,
+    /// The promiser's %Promise mew replying to the request, set once Promised
+    pub response_mew: Option<ActionHash>,
+    pub status: ContractStatus,
+    /// When the request's between dates are over, if it has them
+    pub deadline: Option<Timestamp>,
+    /// The amount written in the request, if it has one
+    pub amount: Option<FlowAmount>,
+}
+
+/// The action and contents of a mew, if the record is a mew
+fn get_mew(mew_hash: ActionHash) -> ExternResult<Option<(Action, Mew)>> {
+    let record = must_get_valid_record(mew_hash)?;
+    let mew: Option<Mew> = record.entry().to_app_option().ok().flatten();
+
+    Ok(mew.map(|mew| (record.action().clone(), mew)))
+}
+
+pub fn validate_create_mew_contract(
+    action: EntryCreationAction,
+    mew_contract: MewContract,
+) -> ExternResult<ValidateCallbackResult> {
+    if mew_contract.requestor == mew_contract.promiser {
+        return Ok(ValidateCallbackResult::Invalid(
+            "You cannot promise to fulfil your own request".into(),
+        ));
+    }
+    match mew_contract.status {
+        ContractStatus::Requested => {
+            if mew_contract.requestor != *action.author() {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Only the requestor can commit a Requested MewContract".into(),
+                ));
+            }
+            if mew_contract.response_mew.is_some() {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "A Requested MewContract cannot have a response mew".into(),
+                ));
+            }
+        }
+        ContractStatus::Promised => {
+            if mew_contract.promiser != *action.author() {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Only the promiser can commit a Promised MewContract".into(),
+                ));
+            }
+        }
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "A MewContract must start as Requested or Promised".into(),
+            ))
+        }
+    }
+
+    let request_validation = validate_request_mew(&mew_contract)?;
+    if request_validation != ValidateCallbackResult::Valid {
+        return Ok(request_validation);
+    }
+    if mew_contract.status == ContractStatus::Promised {
+        return validate_response_mew(&mew_contract);
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_update_mew_contract(
+    action: Update,
+    mew_contract: MewContract,
+    _original_action: EntryCreationAction,
+    original_mew_contract: MewContract,
+) -> ExternResult<ValidateCallbackResult> {
+    if mew_contract.requestor != original_mew_contract.requestor
+        || mew_contract.promiser != original_mew_contract.promiser
+        || mew_contract.request_mew != original_mew_contract.request_mew
+        || mew_contract.deadline != original_mew_contract.deadline
+        || mew_contract.amount != original_mew_contract.amount
+    {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The parties, request, deadline and amount of a MewContract cannot change".into(),
+        ));
+    }
+    if !original_mew_contract
+        .status
+        .next_statuses()
+        .contains(&mew_contract.status)
+    {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "A MewContract cannot go from {:?} to {:?}",
+            original_mew_contract.status, mew_contract.status
+        )));
+    }
+
+    // Each author moves a revision on once, so their transitions of a contract can't fork
+    let agent_activity = must_get_agent_activity(
+        action.author.clone(),
+        ChainFilter::new(action.prev_action.clone()),
+    )?;
+    if agent_activity
+        .iter()
+        .any(|activity| match activity.action.action() {
+            Action::Update(update) => {
+                update.original_action_address == action.original_action_address
+            }
+            _ => false,
+        })
+    {
+        return Ok(ValidateCallbackResult::Invalid(
+            "You already updated this revision of the MewContract".into(),
+        ));
+    }
+
+    let is_allowed_author = match mew_contract.status {
+        ContractStatus::Promised | ContractStatus::Delivered => {
+            action.author == mew_contract.promiser
+        }
+        ContractStatus::Thanked | ContractStatus::Cancelled => {
+            action.author == mew_contract.requestor
+        }
+        ContractStatus::Expired => {
+            action.author == mew_contract.requestor || action.author == mew_contract.promiser
+        }
+        ContractStatus::Requested => false,
+    };
+    if !is_allowed_author {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "You cannot mark this MewContract as {:?}",
+            mew_contract.status
+        )));
+    }
+    if mew_contract.status == ContractStatus::Expired {
+        match mew_contract.deadline {
+            Some(deadline) if action.timestamp >= deadline => {}
+            Some(_) => {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "A MewContract cannot expire before its deadline".into(),
+                ))
+            }
+            None => {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Only a MewContract with a deadline can expire".into(),
+                ))
+            }
+        }
+    }
+
+    // The response mew is set when promising, and fixed after that
+    if mew_contract.status == ContractStatus::Promised {
+        return validate_response_mew(&mew_contract);
+    }
+    if mew_contract.response_mew != original_mew_contract.response_mew {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The response mew of a MewContract cannot change".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_request_mew(mew_contract: &MewContract) -> ExternResult<ValidateCallbackResult> {
+    match get_mew(mew_contract.request_mew.clone())? {
+        Some((request_action, request)) => {
+            if *request_action.author() != mew_contract.requestor {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "The request mew must be authored by the requestor".into(),
+                ));
+            }
+            let (deadline, amount) = match parse_flow(&request.text) {
+                Ok(FlowRequest {
+                    kind: FlowKind::Request,
+                    between,
+                    amount,
+                    ..
+                }) => (between.and_then(|between| between.deadline()), amount),
+                _ => {
+                    return Ok(ValidateCallbackResult::Invalid(
+                        "The request mew must be a %Request".into(),
+                    ))
+                }
+            };
+            if mew_contract.deadline != deadline {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "The deadline must be the end of the request's between dates".into(),
+                ));
+            }
+            if mew_contract.amount != amount {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "The amount must be the request's amount".into(),
+                ));
+            }
+        }
+        None => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "The request mew must be a mew".into(),
+            ))
+        }
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_response_mew(mew_contract: &MewContract) -> ExternResult<ValidateCallbackResult> {
+    let response_mew = match &mew_contract.response_mew {
+        Some(response_mew) => response_mew.clone(),
+        None => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "A Promised MewContract must have a response mew".into(),
+            ))
+        }
+    };
+
+    match get_mew(response_mew)? {
+        Some((response_action, response)) => {
+            if *response_action.author() != mew_contract.promiser {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "The response mew must be authored by the promiser".into(),
+                ));
+            }
+            if !matches!(
+                parse_flow(&response.text),
+                Ok(FlowRequest {
+                    kind: FlowKind::Promise,
+                    ..
+                })
+            ) {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "The response mew must be a %Promise".into(),
+                ));
+            }
+            match response.mew_type {
+                MewType::Reply(parent) if parent == mew_contract.request_mew => {}
+                _ => {
+                    return Ok(ValidateCallbackResult::Invalid(
+                        "The response mew must reply to the request mew".into(),
+                    ))
+                }
+            }
+            if let Some(deadline) = mew_contract.deadline {
+                if response_action.timestamp() >= deadline {
+                    return Ok(ValidateCallbackResult::Invalid(
+                        "Requests cannot be promised after their deadline".into(),
+                    ));
+                }
+            }
+        }
+        None => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "The response mew must be a mew".into(),
+            ))
+        }
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_delete_mew_contract(
+    _action: Delete,
+    _original_action: EntryCreationAction,
+    _original_mew_contract: MewContract,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(
+        "Mew contracts cannot be deleted".into(),
+    ))
+}
+
+pub fn validate_create_link_mew_to_contracts(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash = ActionHash::try_from(target_address).map_err(|err| wasm_error!(err))?;
+    let record = must_get_valid_record(action_hash)?;
+    let mew_contract: MewContract = match record.entry().to_app_option() {
+        Ok(Some(mew_contract)) => mew_contract,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Linked action must reference a MewContract".into(),
+            ))
+        }
+    };
+    if *record.action().author() != action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "You can only link your own mew contracts".into(),
+        ));
+    }
+    if base_address != AnyLinkableHash::from(mew_contract.request_mew) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Mew contracts must be linked from their request mew".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_delete_link_mew_to_contracts(
+    _action: DeleteLink,
+    _original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(
+        "Mew contract links cannot be deleted".into(),
+    ))
+}
+
+pub fn validate_create_link_promiser_to_contracts(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash = ActionHash::try_from(target_address).map_err(|err| wasm_error!(err))?;
+    let record = must_get_valid_record(action_hash)?;
+    let mew_contract: MewContract = match record.entry().to_app_option() {
+        Ok(Some(mew_contract)) => mew_contract,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Linked action must reference a MewContract".into(),
+            ))
+        }
+    };
+    if *record.action().author() != action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "You can only link your own mew contracts".into(),
+        ));
+    }
+    if base_address != AnyLinkableHash::from(mew_contract.promiser) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Mew contracts must be linked from their promiser".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_delete_link_promiser_to_contracts(
+    _action: DeleteLink,
+    _original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(
+        "Mew contract links cannot be deleted".into(),
+    ))
+}
@@ -0,0 +1,199 @@
+use hdi::prelude::*;
+
+/// A follower's request to follow a protected creator
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct FollowRequest {
+    pub creator: AgentPubKey,
+}
+
+/// A creator's acceptance of a FollowRequest, referenced by the follower's CreatorToFollowers link
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct FollowAcceptance {
+    pub follow_request: ActionHash,
+    pub follower: AgentPubKey,
+}
+
+pub fn validate_create_follow_request(
+    action: EntryCreationAction,
+    follow_request: FollowRequest,
+) -> ExternResult<ValidateCallbackResult> {
+    if follow_request.creator == *action.author() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "You cannot request to follow yourself".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_update_follow_request(
+    _action: Update,
+    _follow_request: FollowRequest,
+    _original_action: EntryCreationAction,
+    _original_follow_request: FollowRequest,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(
+        "Follow requests cannot be updated".into(),
+    ))
+}
+
+pub fn validate_delete_follow_request(
+    action: Delete,
+    original_action: EntryCreationAction,
+    _original_follow_request: FollowRequest,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != *original_action.author() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the original action author can delete their FollowRequest".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_create_follow_acceptance(
+    action: EntryCreationAction,
+    follow_acceptance: FollowAcceptance,
+) -> ExternResult<ValidateCallbackResult> {
+    let record = must_get_valid_record(follow_acceptance.follow_request)?;
+    let follow_request: FollowRequest = match record.entry().to_app_option() {
+        Ok(Some(follow_request)) => follow_request,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "A FollowAcceptance must reference a FollowRequest".into(),
+            ))
+        }
+    };
+    if follow_request.creator != *action.author() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the requested creator can accept a FollowRequest".into(),
+        ));
+    }
+    if *record.action().author() != follow_acceptance.follower {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The follower of a FollowAcceptance must be the author of its FollowRequest".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_update_follow_acceptance(
+    _action: Update,
+    _follow_acceptance: FollowAcceptance,
+    _original_action: EntryCreationAction,
+    _original_follow_acceptance: FollowAcceptance,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(
+        "Follow acceptances cannot be updated".into(),
+    ))
+}
+
+pub fn validate_delete_follow_acceptance(
+    action: Delete,
+    original_action: EntryCreationAction,
+    _original_follow_acceptance: FollowAcceptance,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != *original_action.author() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the original action author can delete their FollowAcceptance".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_create_link_creator_to_follow_requests(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash = ActionHash::try_from(target_address).map_err(|err| wasm_error!(err))?;
+    let record = must_get_valid_record(action_hash)?;
+    let follow_request: FollowRequest = match record.entry().to_app_option() {
+        Ok(Some(follow_request)) => follow_request,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Linked action must reference a FollowRequest".into(),
+            ))
+        }
+    };
+    if *record.action().author() != action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "You can only link your own follow requests".into(),
+        ));
+    }
+    if base_address != AnyLinkableHash::from(follow_request.creator) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Follow requests must be linked from the requested creator".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_delete_link_creator_to_follow_requests(
+    action: DeleteLink,
+    original_action: CreateLink,
+    base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    // Either the follower withdraws the request or the creator answers it
+    if action.author != original_action.author && base != AnyLinkableHash::from(action.author) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the follower or the requested creator can remove a follow request".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_create_link_follow_request_to_acceptances(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash = ActionHash::try_from(target_address).map_err(|err| wasm_error!(err))?;
+    let record = must_get_valid_record(action_hash)?;
+    let follow_acceptance: FollowAcceptance = match record.entry().to_app_option() {
+        Ok(Some(follow_acceptance)) => follow_acceptance,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Linked action must reference a FollowAcceptance".into(),
+            ))
+        }
+    };
+    if *record.action().author() != action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "You can only link your own follow acceptances".into(),
+        ));
+    }
+    if base_address != AnyLinkableHash::from(follow_acceptance.follow_request) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Follow acceptances must be linked from the FollowRequest they accept".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_delete_link_follow_request_to_acceptances(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != original_action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "You cannot change which follow requests others accept".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
@@ -1,4 +1,78 @@
+use crate::{FollowAcceptance, LinkTypes};
+use hc_active_links::has_active_link;
+use hc_link_type_utils::get_zome_index;
 use hdi::prelude::*;
+use std::collections::BTreeMap;
+
+/// Profile field marking an account as protected, so only accepted followers may follow it
+pub const PROTECTED_PROFILE_FIELD: &str = "protected";
+
+/// Tag of a CreatorToFollowers link, showing the creator allows the follow
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+pub struct CreatorToFollowersTag {
+    /// The creator's chain head as the follower saw it when following
+    pub creator_chain_head: ActionHash,
+    /// The creator's latest profile revision up to that chain head, if they have one
+    pub creator_profile: Option<ActionHash>,
+    /// The creator's FollowAcceptance, required when that profile is protected
+    pub acceptance: Option<ActionHash>,
+}
+
+impl TryFrom<LinkTag> for CreatorToFollowersTag {
+    type Error = WasmError;
+
+    fn try_from(tag: LinkTag) -> Result<Self, Self::Error> {
+        CreatorToFollowersTag::try_from(SerializedBytes::from(UnsafeBytes::from(tag.into_inner())))
+            .map_err(|err| wasm_error!(err))
+    }
+}
+
+/// The part of a profile entry that says whether the account is protected
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+struct ProfileFields {
+    fields: BTreeMap<String, String>,
+}
+
+pub fn is_profile_protected(fields: &BTreeMap<String, String>) -> bool {
+    fields
+        .get(PROTECTED_PROFILE_FIELD)
+        .map(|value| value == "true")
+        .unwrap_or(false)
+}
+
+/// The latest profile in an agent's activity, with its fields
+///
+/// Entries of the profiles integrity zome are checked to be profiles by deserializing them.
+pub fn get_latest_profile(
+    activity: &[RegisterAgentActivity],
+) -> ExternResult<Option<(ActionHash, BTreeMap<String, String>)>> {
+    let profiles_zome_index = get_zome_index("profiles_integrity")?;
+    let mut profile_actions: Vec<&SignedActionHashed> = activity
+        .iter()
+        .map(|activity| &activity.action)
+        .filter(|action| {
+            matches!(
+                action.action().entry_type(),
+                Some(EntryType::App(app_entry_def)) if app_entry_def.zome_index == profiles_zome_index
+            )
+        })
+        .collect();
+    profile_actions.sort_by_key(|action| std::cmp::Reverse(action.action().action_seq()));
+
+    for action in profile_actions {
+        let entry_hash = match action.action().entry_hash() {
+            Some(entry_hash) => entry_hash.clone(),
+            None => continue,
+        };
+        if let Entry::App(bytes) = must_get_entry(entry_hash)?.as_content() {
+            if let Ok(profile) = ProfileFields::try_from(bytes.0.clone()) {
+                return Ok(Some((action.as_hash().clone(), profile.fields)));
+            }
+        }
+    }
+
+    Ok(None)
+}
 pub fn validate_create_link_follower_to_creators(
     action: CreateLink,
     base_address: AnyLinkableHash,
@@ -10,11 +84,18 @@ pub fn validate_create_link_follower_to_creators(
             "You cannot follow yourself".into(),
         ));
     }
-    if base_address != AnyLinkableHash::from(action.author) {
+    if base_address != AnyLinkableHash::from(action.author.clone()) {
         return Ok(ValidateCallbackResult::Invalid(
             "You cannot change who others follow".into(),
         ));
     }
+    if has_active_link(&action, LinkTypes::FollowerToCreators, |create_link| {
+        create_link.base_address == base_address && create_link.target_address == target_address
+    })? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "You already follow this creator".into(),
+        ));
+    }
 
     Ok(ValidateCallbackResult::Valid)
 }
@@ -37,20 +118,45 @@ pub fn validate_create_link_creator_to_followers(
     action: CreateLink,
     base_address: AnyLinkableHash,
     target_address: AnyLinkableHash,
-    _tag: LinkTag,
+    tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
     if base_address == target_address {
         return Ok(ValidateCallbackResult::Invalid(
             "You cannot follow yourself".into(),
         ));
     }
-    if target_address != AnyLinkableHash::from(action.author) {
+    if target_address != AnyLinkableHash::from(action.author.clone()) {
         return Ok(ValidateCallbackResult::Invalid(
             "You cannot change who another agent follows".into(),
         ));
     }
+    if has_active_link(&action, LinkTypes::CreatorToFollowers, |create_link| {
+        create_link.base_address == base_address && create_link.target_address == target_address
+    })? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "You already follow this creator".into(),
+        ));
+    }
 
-    Ok(ValidateCallbackResult::Valid)
+    // The FollowerToCreators link must be the action right before this one
+    let follower_to_creators_type: ScopedLinkType = LinkTypes::FollowerToCreators.try_into()?;
+    let is_paired = match must_get_action(action.prev_action.clone())?.action() {
+        Action::CreateLink(prev_link) => {
+            prev_link.zome_index == follower_to_creators_type.zome_index
+                && prev_link.link_type == follower_to_creators_type.zome_type
+                && prev_link.base_address == target_address
+                && prev_link.target_address == base_address
+        }
+        _ => false,
+    };
+    if !is_paired {
+        return Ok(ValidateCallbackResult::Invalid(
+            "A follow must create both its FollowerToCreators and CreatorToFollowers links together"
+                .into(),
+        ));
+    }
+
+    validate_creator_to_followers_tag(base_address, target_address, tag)
 }
 pub fn validate_delete_link_creator_to_followers(
     action: DeleteLink,
@@ -67,3 +173,68 @@ pub fn validate_delete_link_creator_to_followers(
 
     Ok(ValidateCallbackResult::Valid)
 }
+
+fn validate_creator_to_followers_tag(
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let tag = match CreatorToFollowersTag::try_from(tag) {
+        Ok(tag) => tag,
+        Err(_) => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Malformed CreatorToFollowers tag".into(),
+            ))
+        }
+    };
+
+    // The creator's profile is looked up in their own chain rather than taken from the tag
+    let creator = match AgentPubKey::try_from(base_address.clone()) {
+        Ok(creator) => creator,
+        Err(_) => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "CreatorToFollowers base address must be an agent".into(),
+            ))
+        }
+    };
+    let creator_activity =
+        must_get_agent_activity(creator, ChainFilter::new(tag.creator_chain_head))?;
+    let latest_profile = get_latest_profile(&creator_activity)?;
+    if tag.creator_profile != latest_profile.as_ref().map(|(hash, _)| hash.clone()) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The referenced profile must be the creator's latest profile".into(),
+        ));
+    }
+    let is_protected = latest_profile.map_or(false, |(_, fields)| is_profile_protected(&fields));
+
+    match tag.acceptance {
+        Some(acceptance) => {
+            let record = must_get_valid_record(acceptance)?;
+            let follow_acceptance: FollowAcceptance = match record.entry().to_app_option() {
+                Ok(Some(follow_acceptance)) => follow_acceptance,
+                _ => {
+                    return Ok(ValidateCallbackResult::Invalid(
+                        "The referenced acceptance must be a FollowAcceptance".into(),
+                    ))
+                }
+            };
+            if AnyLinkableHash::from(record.action().author().clone()) != base_address
+                || AnyLinkableHash::from(follow_acceptance.follower) != target_address
+            {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "The referenced acceptance must be the creator's acceptance of this follower"
+                        .into(),
+                ));
+            }
+        }
+        None => {
+            if is_protected {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Following a protected creator requires their acceptance".into(),
+                ));
+            }
+        }
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
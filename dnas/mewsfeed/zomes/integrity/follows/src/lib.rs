@@ -1,12 +1,25 @@
+pub mod follow_request;
+pub use follow_request::*;
 pub mod follower_to_creators;
 pub use follower_to_creators::*;
 use hdi::prelude::*;
 
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+#[hdk_entry_defs]
+#[unit_enum(UnitEntryTypes)]
+pub enum EntryTypes {
+    FollowRequest(FollowRequest),
+    FollowAcceptance(FollowAcceptance),
+}
+
 #[derive(Serialize, Deserialize)]
 #[hdk_link_types]
 pub enum LinkTypes {
     FollowerToCreators,
     CreatorToFollowers,
+    CreatorToFollowRequests,
+    FollowRequestToAcceptances,
 }
 #[hdk_extern]
 pub fn genesis_self_check(_data: GenesisSelfCheckData) -> ExternResult<ValidateCallbackResult> {
@@ -21,12 +34,14 @@ pub fn validate_agent_joining(
 #[allow(unused_variables)]
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
-    match op.flattened::<(), LinkTypes>()? {
+    match op.flattened::<EntryTypes, LinkTypes>()? {
         FlatOp::StoreEntry(store_entry) => match store_entry {
-            OpEntry::CreateEntry { app_entry, action } => Ok(ValidateCallbackResult::Valid),
+            OpEntry::CreateEntry { app_entry, action } => {
+                validate_create_app_entry(EntryCreationAction::Create(action), app_entry)
+            }
             OpEntry::UpdateEntry {
                 app_entry, action, ..
-            } => Ok(ValidateCallbackResult::Valid),
+            } => validate_create_app_entry(EntryCreationAction::Update(action), app_entry),
             _ => Ok(ValidateCallbackResult::Valid),
         },
         FlatOp::RegisterUpdate(update_entry) => match update_entry {
@@ -35,9 +50,29 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 original_app_entry,
                 app_entry,
                 action,
-            } => Ok(ValidateCallbackResult::Invalid(
-                "There are no entry types in this integrity zome".to_string(),
-            )),
+            } => match (app_entry, original_app_entry) {
+                (
+                    EntryTypes::FollowRequest(follow_request),
+                    EntryTypes::FollowRequest(original_follow_request),
+                ) => validate_update_follow_request(
+                    action,
+                    follow_request,
+                    original_action,
+                    original_follow_request,
+                ),
+                (
+                    EntryTypes::FollowAcceptance(follow_acceptance),
+                    EntryTypes::FollowAcceptance(original_follow_acceptance),
+                ) => validate_update_follow_acceptance(
+                    action,
+                    follow_acceptance,
+                    original_action,
+                    original_follow_acceptance,
+                ),
+                _ => Ok(ValidateCallbackResult::Invalid(
+                    "The updated entry type must be the same as the original entry type".into(),
+                )),
+            },
             _ => Ok(ValidateCallbackResult::Valid),
         },
         FlatOp::RegisterDelete(delete_entry) => match delete_entry {
@@ -45,7 +80,7 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 original_action,
                 original_app_entry,
                 action,
-            } => Ok(ValidateCallbackResult::Valid),
+            } => validate_delete_app_entry(action, original_action, original_app_entry),
             _ => Ok(ValidateCallbackResult::Valid),
         },
         FlatOp::RegisterCreateLink {
@@ -61,6 +96,20 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             LinkTypes::CreatorToFollowers => {
                 validate_create_link_creator_to_followers(action, base_address, target_address, tag)
             }
+            LinkTypes::CreatorToFollowRequests => validate_create_link_creator_to_follow_requests(
+                action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::FollowRequestToAcceptances => {
+                validate_create_link_follow_request_to_acceptances(
+                    action,
+                    base_address,
+                    target_address,
+                    tag,
+                )
+            }
         },
         FlatOp::RegisterDeleteLink {
             link_type,
@@ -84,20 +133,82 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 target_address,
                 tag,
             ),
+            LinkTypes::CreatorToFollowRequests => validate_delete_link_creator_to_follow_requests(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::FollowRequestToAcceptances => {
+                validate_delete_link_follow_request_to_acceptances(
+                    action,
+                    original_action,
+                    base_address,
+                    target_address,
+                    tag,
+                )
+            }
         },
         FlatOp::StoreRecord(store_record) => match store_record {
-            OpRecord::CreateEntry { app_entry, action } => Ok(ValidateCallbackResult::Valid),
-            OpRecord::UpdateEntry {
-                original_action_hash,
-                app_entry,
-                action,
-                ..
-            } => Ok(ValidateCallbackResult::Valid),
+            OpRecord::CreateEntry { app_entry, action } => {
+                validate_create_app_entry(EntryCreationAction::Create(action), app_entry)
+            }
+            // Neither entry type can be updated, so the original isn't needed to reject an update
+            OpRecord::UpdateEntry { app_entry, .. } => match app_entry {
+                EntryTypes::FollowRequest(_) => Ok(ValidateCallbackResult::Invalid(
+                    "Follow requests cannot be updated".into(),
+                )),
+                EntryTypes::FollowAcceptance(_) => Ok(ValidateCallbackResult::Invalid(
+                    "Follow acceptances cannot be updated".into(),
+                )),
+            },
             OpRecord::DeleteEntry {
                 original_action_hash,
                 action,
                 ..
-            } => Ok(ValidateCallbackResult::Valid),
+            } => {
+                let original_record = must_get_valid_record(original_action_hash)?;
+                let original_action = match original_record.action().clone() {
+                    Action::Create(create) => EntryCreationAction::Create(create),
+                    Action::Update(update) => EntryCreationAction::Update(update),
+                    _ => {
+                        return Ok(ValidateCallbackResult::Invalid(
+                            "Original action for a delete must be a Create or Update action"
+                                .to_string(),
+                        ));
+                    }
+                };
+                let app_entry_type = match original_action.entry_type() {
+                    EntryType::App(app_entry_type) => app_entry_type,
+                    _ => {
+                        return Ok(ValidateCallbackResult::Valid);
+                    }
+                };
+                let entry = match original_record.entry().as_option() {
+                    Some(entry) => entry,
+                    None => {
+                        return Ok(ValidateCallbackResult::Invalid(
+                            "Original record for a delete of a public entry must contain an entry"
+                                .to_string(),
+                        ));
+                    }
+                };
+                let original_app_entry = match EntryTypes::deserialize_from_type(
+                    app_entry_type.zome_index,
+                    app_entry_type.entry_index,
+                    entry,
+                )? {
+                    Some(app_entry) => app_entry,
+                    None => {
+                        return Ok(ValidateCallbackResult::Invalid(
+                            "Original app entry must be one of the defined entry types for this zome"
+                                .to_string(),
+                        ));
+                    }
+                };
+                validate_delete_app_entry(action, original_action, original_app_entry)
+            }
             OpRecord::CreateLink {
                 base_address,
                 target_address,
@@ -117,6 +228,22 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                     target_address,
                     tag,
                 ),
+                LinkTypes::CreatorToFollowRequests => {
+                    validate_create_link_creator_to_follow_requests(
+                        action,
+                        base_address,
+                        target_address,
+                        tag,
+                    )
+                }
+                LinkTypes::FollowRequestToAcceptances => {
+                    validate_create_link_follow_request_to_acceptances(
+                        action,
+                        base_address,
+                        target_address,
+                        tag,
+                    )
+                }
             },
             OpRecord::DeleteLink {
                 original_action_hash,
@@ -154,6 +281,24 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                         create_link.target_address,
                         create_link.tag,
                     ),
+                    LinkTypes::CreatorToFollowRequests => {
+                        validate_delete_link_creator_to_follow_requests(
+                            action,
+                            create_link.clone(),
+                            base_address,
+                            create_link.target_address,
+                            create_link.tag,
+                        )
+                    }
+                    LinkTypes::FollowRequestToAcceptances => {
+                        validate_delete_link_follow_request_to_acceptances(
+                            action,
+                            create_link.clone(),
+                            base_address,
+                            create_link.target_address,
+                            create_link.tag,
+                        )
+                    }
                 }
             }
             OpRecord::CreatePrivateEntry { .. } => Ok(ValidateCallbackResult::Valid),
@@ -189,3 +334,32 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
         },
     }
 }
+
+fn validate_create_app_entry(
+    action: EntryCreationAction,
+    app_entry: EntryTypes,
+) -> ExternResult<ValidateCallbackResult> {
+    match app_entry {
+        EntryTypes::FollowRequest(follow_request) => {
+            validate_create_follow_request(action, follow_request)
+        }
+        EntryTypes::FollowAcceptance(follow_acceptance) => {
+            validate_create_follow_acceptance(action, follow_acceptance)
+        }
+    }
+}
+
+fn validate_delete_app_entry(
+    action: Delete,
+    original_action: EntryCreationAction,
+    original_app_entry: EntryTypes,
+) -> ExternResult<ValidateCallbackResult> {
+    match original_app_entry {
+        EntryTypes::FollowRequest(original_follow_request) => {
+            validate_delete_follow_request(action, original_action, original_follow_request)
+        }
+        EntryTypes::FollowAcceptance(original_follow_acceptance) => {
+            validate_delete_follow_acceptance(action, original_action, original_follow_acceptance)
+        }
+    }
+}
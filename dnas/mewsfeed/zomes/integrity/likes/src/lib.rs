@@ -1,6 +1,8 @@
 pub mod liker_to_hashes;
+pub mod reaction;
 use hdi::prelude::*;
 pub use liker_to_hashes::*;
+pub use reaction::*;
 
 #[derive(Serialize, Deserialize)]
 #[hdk_link_types]
@@ -1,16 +1,35 @@
+use crate::{validate_reaction_tag, LinkTypes, ReactionTag};
+use hc_active_links::has_active_link;
 use hdi::prelude::*;
+
 pub fn validate_create_link_liker_to_hashes(
     action: CreateLink,
     base_address: AnyLinkableHash,
-    _target_address: AnyLinkableHash,
-    _tag: LinkTag,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
-    if base_address != AnyLinkableHash::from(action.author) {
+    if base_address != AnyLinkableHash::from(action.author.clone()) {
         return Ok(ValidateCallbackResult::Invalid(
             "You cannot change who others like".into(),
         ));
     }
 
+    let reaction_tag_validation = validate_reaction_tag(tag.clone())?;
+    if reaction_tag_validation != ValidateCallbackResult::Valid {
+        return Ok(reaction_tag_validation);
+    }
+    if has_active_reaction_link(
+        &action,
+        LinkTypes::LikerToHashes,
+        &base_address,
+        &target_address,
+        tag,
+    )? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "You already reacted to this with this reaction".into(),
+        ));
+    }
+
     Ok(ValidateCallbackResult::Valid)
 }
 pub fn validate_delete_link_liker_to_hashes(
@@ -30,16 +49,32 @@ pub fn validate_delete_link_liker_to_hashes(
 }
 pub fn validate_create_link_hash_to_likers(
     action: CreateLink,
-    _base_address: AnyLinkableHash,
+    base_address: AnyLinkableHash,
     target_address: AnyLinkableHash,
-    _tag: LinkTag,
+    tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
-    if target_address != AnyLinkableHash::from(action.author) {
+    if target_address != AnyLinkableHash::from(action.author.clone()) {
         return Ok(ValidateCallbackResult::Invalid(
             "You cannot change who others like".into(),
         ));
     }
 
+    let reaction_tag_validation = validate_reaction_tag(tag.clone())?;
+    if reaction_tag_validation != ValidateCallbackResult::Valid {
+        return Ok(reaction_tag_validation);
+    }
+    if has_active_reaction_link(
+        &action,
+        LinkTypes::HashToLikers,
+        &base_address,
+        &target_address,
+        tag,
+    )? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "You already reacted to this with this reaction".into(),
+        ));
+    }
+
     Ok(ValidateCallbackResult::Valid)
 }
 pub fn validate_delete_link_hash_to_likers(
@@ -57,3 +92,22 @@ pub fn validate_delete_link_hash_to_likers(
 
     Ok(ValidateCallbackResult::Valid)
 }
+
+/// Whether the link author already has an undeleted link of this type and reaction from base to target
+fn has_active_reaction_link(
+    action: &CreateLink,
+    link_type: LinkTypes,
+    base_address: &AnyLinkableHash,
+    target_address: &AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<bool> {
+    let reaction = ReactionTag::try_from(tag)?.reaction;
+
+    has_active_link(action, link_type, |create_link| {
+        create_link.base_address == *base_address
+            && create_link.target_address == *target_address
+            && ReactionTag::try_from(create_link.tag.clone())
+                .map(|reaction_tag| reaction_tag.reaction == reaction)
+                .unwrap_or(false)
+    })
+}
@@ -0,0 +1,80 @@
+use hdi::prelude::*;
+pub use mews_types::LICK_REACTION;
+
+/// Reactions allowed when `allowed_reactions` isn't set in DNA properties
+pub const DEFAULT_ALLOWED_REACTIONS: [&str; 3] = [LICK_REACTION, "purr", "hiss"];
+
+/// Tag of LikerToHashes and HashToLikers links, naming the kind of reaction
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq, Eq)]
+pub struct ReactionTag {
+    /// A reaction name such as "purr", or a custom emoji shortcode such as ":sparkles:"
+    pub reaction: String,
+}
+
+impl Default for ReactionTag {
+    fn default() -> Self {
+        ReactionTag {
+            reaction: LICK_REACTION.into(),
+        }
+    }
+}
+
+impl TryFrom<LinkTag> for ReactionTag {
+    type Error = WasmError;
+
+    fn try_from(tag: LinkTag) -> Result<Self, Self::Error> {
+        if tag.0.is_empty() {
+            return Ok(ReactionTag::default());
+        }
+
+        ReactionTag::try_from(SerializedBytes::from(UnsafeBytes::from(tag.into_inner())))
+            .map_err(|err| wasm_error!(err))
+    }
+}
+
+/// The part of the DNA properties read by the likes zome
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+struct LikesDnaProperties {
+    allowed_reactions: Option<Vec<String>>,
+}
+
+/// Reactions allowed in this DNA, always including licks since empty tags are read as licks
+pub fn get_allowed_reactions() -> ExternResult<Vec<String>> {
+    let properties = LikesDnaProperties::try_from(dna_info()?.modifiers.properties)
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.into())))?;
+
+    let mut allowed_reactions = properties.allowed_reactions.unwrap_or_else(|| {
+        DEFAULT_ALLOWED_REACTIONS
+            .iter()
+            .map(|reaction| reaction.to_string())
+            .collect()
+    });
+    if !allowed_reactions
+        .iter()
+        .any(|reaction| reaction == LICK_REACTION)
+    {
+        allowed_reactions.insert(0, LICK_REACTION.into());
+    }
+
+    Ok(allowed_reactions)
+}
+
+pub fn validate_reaction_tag(tag: LinkTag) -> ExternResult<ValidateCallbackResult> {
+    let reaction_tag = match ReactionTag::try_from(tag) {
+        Ok(reaction_tag) => reaction_tag,
+        Err(_) => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Malformed reaction tag".into(),
+            ))
+        }
+    };
+
+    if !get_allowed_reactions()?.contains(&reaction_tag.reaction) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Reaction '{}' is not allowed",
+            reaction_tag.reaction
+        )));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
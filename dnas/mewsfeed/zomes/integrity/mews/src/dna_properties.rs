@@ -1,9 +1,25 @@
 use hdi::prelude::*;
 
+/// How mew text length is measured for `mew_characters_min` and `mew_characters_max`
+#[derive(Debug, Serialize, Deserialize, SerializedBytes, Clone, PartialEq, Eq, Default)]
+pub enum MewLengthCounting {
+    /// UTF-8 bytes of the mew text
+    Bytes,
+    /// User-perceived characters (extended grapheme clusters)
+    #[default]
+    Graphemes,
+}
+
 #[derive(Debug, Serialize, Deserialize, SerializedBytes, Clone)]
 pub struct DnaProperties {
     pub mew_characters_min: Option<usize>,
     pub mew_characters_max: Option<usize>,
+    pub mew_edit_window_seconds: Option<u64>,
+    pub mew_length_counting: Option<MewLengthCounting>,
+    /// Reactions accepted by the likes zome, "lick", "purr" and "hiss" when not set
+    pub allowed_reactions: Option<Vec<String>>,
+    /// Most mews an agent can have pinned at once, unlimited when not set
+    pub max_pins: Option<usize>,
 }
 
 #[hdk_extern]
@@ -1,20 +1,56 @@
+use crate::make_tag_result_path_hash;
+use crate::mew::mew_or_updates_by_author_match;
 use hdi::prelude::*;
+use mews_types::*;
 
 pub fn validate_create_link_hashtag_to_mews(
-    _action: CreateLink,
-    _base_address: AnyLinkableHash,
+    action: CreateLink,
+    base_address: AnyLinkableHash,
     target_address: AnyLinkableHash,
-    _tag: LinkTag,
+    tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
     let action_hash = ActionHash::try_from(target_address).map_err(|err| wasm_error!(err))?;
-    let record = must_get_valid_record(action_hash)?;
-    let _mew: crate::Mew = record
+    let record = must_get_valid_record(action_hash.clone())?;
+    let mew: crate::Mew = record
         .entry()
         .to_app_option()
         .map_err(|e| wasm_error!(e))?
         .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
             "Linked action must reference an entry"
         ))))?;
+
+    if action.author != *record.action().author() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the mew author can create their HashtagToMews links".into(),
+        ));
+    }
+
+    // Tag should be a utf8 string
+    let hashtag = String::from_utf8(tag.into_inner()).map_err(|_| {
+        wasm_error!(WasmErrorInner::Guest(
+            "Failed to deserialize link tag to string".into()
+        ))
+    })?;
+
+    // Base address should be the prefix index result path of the hashtag
+    let base_address_entry_hash =
+        EntryHash::try_from(base_address).map_err(|err| wasm_error!(err))?;
+    if base_address_entry_hash != make_tag_result_path_hash(hashtag.clone())? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "HashtagToMews base address must be the prefix index path of the hashtag".into(),
+        ));
+    }
+
+    // Hashtag should appear in the mew, or in a revision of it made before this link
+    if !mew_or_updates_by_author_match(&action, action_hash, &mew, |mew| {
+        get_hashtags(&mew.text).contains(&hashtag)
+    })? {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Mew does not contain the hashtag {}",
+            hashtag
+        )));
+    }
+
     Ok(ValidateCallbackResult::Valid)
 }
 
@@ -8,6 +8,8 @@ pub mod cashtag_to_mews;
 pub use cashtag_to_mews::*;
 pub mod dna_properties;
 pub use dna_properties::*;
+pub mod mew_length;
+pub use mew_length::*;
 pub mod agent_mews;
 pub use agent_mews::*;
 pub mod all_mews;
@@ -16,6 +18,14 @@ pub mod prefix_index_to_cashtags;
 pub use prefix_index_to_cashtags::*;
 pub mod prefix_index_to_hashtags;
 pub use prefix_index_to_hashtags::*;
+pub mod time_bucket_to_mews;
+pub use time_bucket_to_mews::*;
+pub mod time_bucket_to_notifications;
+pub use time_bucket_to_notifications::*;
+pub mod notification_preferences;
+pub use notification_preferences::*;
+pub mod notifications_read;
+pub use notifications_read::*;
 pub mod mew;
 use hdi::prelude::*;
 pub use mew::*;
@@ -26,12 +36,28 @@ pub fn make_tag_prefix_index() -> ExternResult<PrefixIndex> {
     PrefixIndex::new("prefix_index".into(), LinkTypes::PrefixIndex, 3, 3)
 }
 
+/// Hash of the prefix index result path that hashtag and cashtag links are based on
+pub fn make_tag_result_path_hash(tag: String) -> ExternResult<EntryHash> {
+    let mut tag_text = tag.clone();
+    if !tag_text.is_empty() {
+        tag_text.remove(0);
+    }
+
+    make_tag_prefix_index()?
+        .make_result_path(tag_text, Some(tag))?
+        .path_entry_hash()
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(tag = "type")]
 #[hdk_entry_defs]
 #[unit_enum(UnitEntryTypes)]
 pub enum EntryTypes {
     Mew(Mew),
+    #[entry_def(visibility = "private")]
+    NotificationsRead(NotificationsRead),
+    #[entry_def(visibility = "private")]
+    NotificationPreferences(NotificationPreferences),
 }
 
 #[derive(Serialize, Deserialize)]
@@ -46,6 +72,9 @@ pub enum LinkTypes {
     MentionToMews,
     HashtagToMews,
     CashtagToMews,
+    TimeBucket,
+    TimeBucketToMews,
+    TimeBucketToNotifications,
 }
 
 #[hdk_extern]
@@ -70,6 +99,18 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 EntryTypes::Mew(mew) => {
                     validate_create_mew(EntryCreationAction::Create(action), mew)
                 }
+                EntryTypes::NotificationsRead(notifications_read) => {
+                    validate_create_notifications_read(
+                        EntryCreationAction::Create(action),
+                        notifications_read,
+                    )
+                }
+                EntryTypes::NotificationPreferences(notification_preferences) => {
+                    validate_create_notification_preferences(
+                        EntryCreationAction::Create(action),
+                        notification_preferences,
+                    )
+                }
             },
             OpEntry::UpdateEntry {
                 app_entry, action, ..
@@ -77,6 +118,18 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 EntryTypes::Mew(mew) => {
                     validate_create_mew(EntryCreationAction::Update(action), mew)
                 }
+                EntryTypes::NotificationsRead(notifications_read) => {
+                    validate_create_notifications_read(
+                        EntryCreationAction::Update(action),
+                        notifications_read,
+                    )
+                }
+                EntryTypes::NotificationPreferences(notification_preferences) => {
+                    validate_create_notification_preferences(
+                        EntryCreationAction::Update(action),
+                        notification_preferences,
+                    )
+                }
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
@@ -90,6 +143,27 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 (EntryTypes::Mew(mew), EntryTypes::Mew(original_mew)) => {
                     validate_update_mew(action, mew, original_action, original_mew)
                 }
+                (
+                    EntryTypes::NotificationsRead(notifications_read),
+                    EntryTypes::NotificationsRead(original_notifications_read),
+                ) => validate_update_notifications_read(
+                    action,
+                    notifications_read,
+                    original_action,
+                    original_notifications_read,
+                ),
+                (
+                    EntryTypes::NotificationPreferences(notification_preferences),
+                    EntryTypes::NotificationPreferences(original_notification_preferences),
+                ) => validate_update_notification_preferences(
+                    action,
+                    notification_preferences,
+                    original_action,
+                    original_notification_preferences,
+                ),
+                _ => Ok(ValidateCallbackResult::Invalid(
+                    "The updated entry type must be the same as the original entry type".into(),
+                )),
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
@@ -100,6 +174,16 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 action,
             } => match original_app_entry {
                 EntryTypes::Mew(mew) => validate_delete_mew(action, original_action, mew),
+                EntryTypes::NotificationsRead(notifications_read) => {
+                    validate_delete_notifications_read(action, original_action, notifications_read)
+                }
+                EntryTypes::NotificationPreferences(notification_preferences) => {
+                    validate_delete_notification_preferences(
+                        action,
+                        original_action,
+                        notification_preferences,
+                    )
+                }
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
@@ -143,6 +227,20 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             LinkTypes::CashtagToMews => {
                 validate_create_link_cashtag_to_mews(action, base_address, target_address, tag)
             }
+            LinkTypes::TimeBucket => {
+                validate_create_link_time_bucket(action, base_address, target_address, tag)
+            }
+            LinkTypes::TimeBucketToMews => {
+                validate_create_link_time_bucket_to_mews(action, base_address, target_address, tag)
+            }
+            LinkTypes::TimeBucketToNotifications => {
+                validate_create_link_time_bucket_to_notifications(
+                    action,
+                    base_address,
+                    target_address,
+                    tag,
+                )
+            }
         },
         FlatOp::RegisterDeleteLink {
             link_type,
@@ -211,12 +309,47 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 target_address,
                 tag,
             ),
+            LinkTypes::TimeBucket => validate_delete_link_time_bucket(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::TimeBucketToMews => validate_delete_link_time_bucket_to_mews(
+                action,
+                original_action,
+                base_address,
+                target_address,
+                tag,
+            ),
+            LinkTypes::TimeBucketToNotifications => {
+                validate_delete_link_time_bucket_to_notifications(
+                    action,
+                    original_action,
+                    base_address,
+                    target_address,
+                    tag,
+                )
+            }
         },
         FlatOp::StoreRecord(store_record) => match store_record {
             OpRecord::CreateEntry { app_entry, action } => match app_entry {
                 EntryTypes::Mew(mew) => {
                     validate_create_mew(EntryCreationAction::Create(action), mew)
                 }
+                EntryTypes::NotificationsRead(notifications_read) => {
+                    validate_create_notifications_read(
+                        EntryCreationAction::Create(action),
+                        notifications_read,
+                    )
+                }
+                EntryTypes::NotificationPreferences(notification_preferences) => {
+                    validate_create_notification_preferences(
+                        EntryCreationAction::Create(action),
+                        notification_preferences,
+                    )
+                }
             },
             OpRecord::UpdateEntry {
                 original_action_hash,
@@ -263,6 +396,67 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                             Ok(result)
                         }
                     }
+                    EntryTypes::NotificationsRead(notifications_read) => {
+                        let result = validate_create_notifications_read(
+                            EntryCreationAction::Update(action.clone()),
+                            notifications_read.clone(),
+                        )?;
+                        if let ValidateCallbackResult::Valid = result {
+                            let original_notifications_read: Option<NotificationsRead> =
+                                original_record
+                                    .entry()
+                                    .to_app_option()
+                                    .map_err(|e| wasm_error!(e))?;
+                            let original_notifications_read = match original_notifications_read {
+                                Some(notifications_read) => notifications_read,
+                                None => {
+                                    return Ok(ValidateCallbackResult::Invalid(
+                                        "The updated entry type must be the same as the original entry type"
+                                            .to_string(),
+                                    ));
+                                }
+                            };
+                            validate_update_notifications_read(
+                                action,
+                                notifications_read,
+                                original_action,
+                                original_notifications_read,
+                            )
+                        } else {
+                            Ok(result)
+                        }
+                    }
+                    EntryTypes::NotificationPreferences(notification_preferences) => {
+                        let result = validate_create_notification_preferences(
+                            EntryCreationAction::Update(action.clone()),
+                            notification_preferences.clone(),
+                        )?;
+                        if let ValidateCallbackResult::Valid = result {
+                            let original_notification_preferences: Option<NotificationPreferences> =
+                                original_record
+                                    .entry()
+                                    .to_app_option()
+                                    .map_err(|e| wasm_error!(e))?;
+                            let original_notification_preferences =
+                                match original_notification_preferences {
+                                    Some(notification_preferences) => notification_preferences,
+                                    None => {
+                                        return Ok(ValidateCallbackResult::Invalid(
+                                            "The updated entry type must be the same as the original entry type"
+                                                .to_string(),
+                                        ));
+                                    }
+                                };
+                            validate_update_notification_preferences(
+                                action,
+                                notification_preferences,
+                                original_action,
+                                original_notification_preferences,
+                            )
+                        } else {
+                            Ok(result)
+                        }
+                    }
                 }
             }
             OpRecord::DeleteEntry {
@@ -322,6 +516,20 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                     EntryTypes::Mew(original_mew) => {
                         validate_delete_mew(action, original_action, original_mew)
                     }
+                    EntryTypes::NotificationsRead(original_notifications_read) => {
+                        validate_delete_notifications_read(
+                            action,
+                            original_action,
+                            original_notifications_read,
+                        )
+                    }
+                    EntryTypes::NotificationPreferences(original_notification_preferences) => {
+                        validate_delete_notification_preferences(
+                            action,
+                            original_action,
+                            original_notification_preferences,
+                        )
+                    }
                 }
             }
             OpRecord::CreateLink {
@@ -364,6 +572,23 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 LinkTypes::CashtagToMews => {
                     validate_create_link_cashtag_to_mews(action, base_address, target_address, tag)
                 }
+                LinkTypes::TimeBucket => {
+                    validate_create_link_time_bucket(action, base_address, target_address, tag)
+                }
+                LinkTypes::TimeBucketToMews => validate_create_link_time_bucket_to_mews(
+                    action,
+                    base_address,
+                    target_address,
+                    tag,
+                ),
+                LinkTypes::TimeBucketToNotifications => {
+                    validate_create_link_time_bucket_to_notifications(
+                        action,
+                        base_address,
+                        target_address,
+                        tag,
+                    )
+                }
             },
             OpRecord::DeleteLink {
                 original_action_hash,
@@ -450,6 +675,29 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                         create_link.target_address,
                         create_link.tag,
                     ),
+                    LinkTypes::TimeBucket => validate_delete_link_time_bucket(
+                        action,
+                        create_link.clone(),
+                        base_address,
+                        create_link.target_address,
+                        create_link.tag,
+                    ),
+                    LinkTypes::TimeBucketToMews => validate_delete_link_time_bucket_to_mews(
+                        action,
+                        create_link.clone(),
+                        base_address,
+                        create_link.target_address,
+                        create_link.tag,
+                    ),
+                    LinkTypes::TimeBucketToNotifications => {
+                        validate_delete_link_time_bucket_to_notifications(
+                            action,
+                            create_link.clone(),
+                            base_address,
+                            create_link.target_address,
+                            create_link.tag,
+                        )
+                    }
                 }
             }
             OpRecord::CreatePrivateEntry { .. } => Ok(ValidateCallbackResult::Valid),
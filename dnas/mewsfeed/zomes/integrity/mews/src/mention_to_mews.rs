@@ -1,15 +1,17 @@
+use crate::mew::mew_or_updates_by_author_match;
 use hdi::prelude::*;
+use mews_types::*;
 
 pub fn validate_create_link_mention_to_mews(
-    _action: CreateLink,
+    action: CreateLink,
     base_address: AnyLinkableHash,
     target_address: AnyLinkableHash,
     _tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
     // Check the entry type for the given action hash
     let action_hash = ActionHash::try_from(target_address).map_err(|err| wasm_error!(err))?;
-    let record = must_get_valid_record(action_hash)?;
-    let _mew: crate::Mew = record
+    let record = must_get_valid_record(action_hash.clone())?;
+    let mew: crate::Mew = record
         .entry()
         .to_app_option()
         .map_err(|e| wasm_error!(e))?
@@ -17,11 +19,30 @@ pub fn validate_create_link_mention_to_mews(
             "Linked action must reference an entry"
         ))))?;
 
+    if action.author != *record.action().author() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the mew author can create their MentionToMews links".into(),
+        ));
+    }
+
     let base_address_entry_hash =
         EntryHash::try_from(base_address).map_err(|err| wasm_error!(err))?;
-    if AgentPubKey::try_from(base_address_entry_hash).is_err() {
+    let mention = match AgentPubKey::try_from(base_address_entry_hash) {
+        Ok(agent) => agent,
+        Err(_) => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Base addesss of MentionToMew link must be an AgentPubKey".into(),
+            ))
+        }
+    };
+
+    // Mentioned agent should be in the mew's links, or in a revision of it made before this link
+    let mention_target = LinkTarget::Mention(mention);
+    if !mew_or_updates_by_author_match(&action, action_hash, &mew, |mew| {
+        mew.links.contains(&mention_target)
+    })? {
         return Ok(ValidateCallbackResult::Invalid(
-            "Base addesss of MentionToMew link must be an AgentPubKey".into(),
+            "MentionToMews base address must be mentioned in the mew".into(),
         ));
     }
 
@@ -1,4 +1,5 @@
 use crate::dna_properties::*;
+use crate::mew_length::*;
 use hdi::prelude::*;
 use mews_types::*;
 
@@ -11,8 +12,10 @@ pub fn validate_create_mew(
     // Validate min & max mew length by DNA properties setting
     match mew.mew_type {
         MewType::Original | MewType::Reply(_) | MewType::Quote(_) => {
+            let text_length = mew_length(&mew, &properties.mew_length_counting.unwrap_or_default());
+
             if let Some(mew_characters_min) = properties.mew_characters_min {
-                if mew.text.len() < mew_characters_min {
+                if text_length < mew_characters_min {
                     return Ok(ValidateCallbackResult::Invalid(format!(
                         "mew must contain at least {} characters",
                         mew_characters_min
@@ -22,7 +25,7 @@ pub fn validate_create_mew(
 
             // Validate maximum mew length, if set in DNA properties
             if let Some(mew_characters_max) = properties.mew_characters_max {
-                if mew.text.len() > mew_characters_max {
+                if text_length > mew_characters_max {
                     return Ok(ValidateCallbackResult::Invalid(format!(
                         "mew must contain at most {} characters",
                         mew_characters_max
@@ -95,15 +98,70 @@ pub fn validate_create_mew(
     Ok(ValidateCallbackResult::Valid)
 }
 
+/// The mew that a reply, quote or mewmew refers to
+pub fn get_original_mew_hash(mew: &Mew) -> Option<ActionHash> {
+    match &mew.mew_type {
+        MewType::Original => None,
+        MewType::Reply(hash) | MewType::Quote(hash) | MewType::Mewmew(hash) => Some(hash.clone()),
+    }
+}
+
 pub fn validate_update_mew(
-    _action: Update,
-    _mew: Mew,
-    _original_action: EntryCreationAction,
-    _original_mew: Mew,
+    action: Update,
+    mew: Mew,
+    original_action: EntryCreationAction,
+    original_mew: Mew,
 ) -> ExternResult<ValidateCallbackResult> {
-    Ok(ValidateCallbackResult::Invalid(
-        "Mews cannot be updated".into(),
-    ))
+    let properties = get_dna_properties(())?;
+
+    // Editing is only enabled when an edit window is set in DNA properties
+    let mew_edit_window_seconds = match properties.mew_edit_window_seconds {
+        Some(seconds) => seconds,
+        None => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Mews cannot be updated".into(),
+            ))
+        }
+    };
+
+    if action.author != *original_action.author() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the original action author can update their mew".into(),
+        ));
+    }
+
+    // All revisions update the original mew, so the edit window is measured from its creation
+    if let EntryCreationAction::Update(_) = original_action {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Mew updates must reference the original mew".into(),
+        ));
+    }
+
+    // A window too long to count in microseconds never closes
+    let edit_window_micros = i64::try_from(mew_edit_window_seconds)
+        .ok()
+        .and_then(|seconds| seconds.checked_mul(1_000_000));
+    let elapsed_micros = action.timestamp.as_micros() - original_action.timestamp().as_micros();
+    if edit_window_micros.map_or(false, |window_micros| elapsed_micros > window_micros) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "mew can only be updated within {} seconds of being created",
+            mew_edit_window_seconds
+        )));
+    }
+
+    if let MewType::Mewmew(_) = original_mew.mew_type {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Mewmews cannot be updated".into(),
+        ));
+    }
+
+    if mew.mew_type != original_mew.mew_type {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Mew type cannot be changed by an update".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
 }
 
 pub fn validate_delete_mew(
@@ -119,3 +177,49 @@ pub fn validate_delete_mew(
 
     Ok(ValidateCallbackResult::Valid)
 }
+
+/// Revisions of a mew committed by the author of a link before the link itself
+///
+/// Tag and mention links for an edited mew target the original mew, so link validation needs
+/// the revisions to see the text the links were created from.
+pub fn get_mew_updates_by_author(
+    action: &CreateLink,
+    original_mew_hash: ActionHash,
+) -> ExternResult<Vec<Mew>> {
+    let agent_activity = must_get_agent_activity(
+        action.author.clone(),
+        ChainFilter::new(action.prev_action.clone()).include_cached_entries(),
+    )?;
+
+    let mut mews: Vec<Mew> = vec![];
+    for agent_activity in agent_activity {
+        if let Action::Update(update) = agent_activity.action.action() {
+            if update.original_action_address != original_mew_hash {
+                continue;
+            }
+            // Only an entry that isn't a mew is skipped, failing to fetch it is an error
+            let entry = must_get_entry(update.entry_hash.clone())?;
+            if let Ok(mew) = Mew::try_from(entry.as_content()) {
+                mews.push(mew);
+            }
+        }
+    }
+
+    Ok(mews)
+}
+
+/// Whether the mew, or a revision of it committed by the link author before the link, matches
+pub fn mew_or_updates_by_author_match(
+    action: &CreateLink,
+    mew_hash: ActionHash,
+    mew: &Mew,
+    predicate: impl Fn(&Mew) -> bool,
+) -> ExternResult<bool> {
+    if predicate(mew) {
+        return Ok(true);
+    }
+
+    Ok(get_mew_updates_by_author(action, mew_hash)?
+        .iter()
+        .any(|update| predicate(update)))
+}
@@ -0,0 +1,34 @@
+use crate::dna_properties::MewLengthCounting;
+use mews_types::*;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Number of characters a url link counts for, regardless of the length of its label or url
+pub const MEW_URL_LINK_CHARACTERS: usize = 23;
+
+/// Length of a mew's text as validated against `mew_characters_min` and `mew_characters_max`
+///
+/// When counting graphemes, each link tag (`^label`) backed by a `LinkTarget::Url` counts as
+/// `MEW_URL_LINK_CHARACTERS`, and mentions count as their displayed `@nickname` text.
+pub fn mew_length(mew: &Mew, counting: &MewLengthCounting) -> usize {
+    match counting {
+        MewLengthCounting::Bytes => mew.text.len(),
+        MewLengthCounting::Graphemes => {
+            let url_links_count = mew
+                .links
+                .iter()
+                .filter(|link| matches!(link, LinkTarget::Url(_)))
+                .count();
+            let url_link_tags = parse_tags(&mew.text, LINK_SYMBOL)
+                .into_iter()
+                .take(url_links_count)
+                .collect::<Vec<&str>>();
+            let url_link_tags_length: usize = url_link_tags
+                .iter()
+                .map(|tag| tag.graphemes(true).count())
+                .sum();
+
+            mew.text.graphemes(true).count() - url_link_tags_length
+                + url_link_tags.len() * MEW_URL_LINK_CHARACTERS
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use hdi::prelude::*;
+use mews_types::NotificationPreferences;
+
+pub fn validate_create_notification_preferences(
+    _action: EntryCreationAction,
+    _notification_preferences: NotificationPreferences,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_update_notification_preferences(
+    action: Update,
+    _notification_preferences: NotificationPreferences,
+    original_action: EntryCreationAction,
+    _original_notification_preferences: NotificationPreferences,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != *original_action.author() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the original action author can update their NotificationPreferences".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_delete_notification_preferences(
+    action: Delete,
+    original_action: EntryCreationAction,
+    _original_notification_preferences: NotificationPreferences,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != *original_action.author() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the original action author can delete their NotificationPreferences".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
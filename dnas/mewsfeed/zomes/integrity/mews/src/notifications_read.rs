@@ -0,0 +1,50 @@
+use hdi::prelude::*;
+use mews_types::NotificationsRead;
+
+pub fn validate_create_notifications_read(
+    action: EntryCreationAction,
+    notifications_read: NotificationsRead,
+) -> ExternResult<ValidateCallbackResult> {
+    if notifications_read.last_read_timestamp > *action.timestamp() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Notifications cannot be marked read later than the time they are marked".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_update_notifications_read(
+    action: Update,
+    notifications_read: NotificationsRead,
+    original_action: EntryCreationAction,
+    original_notifications_read: NotificationsRead,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != *original_action.author() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the original action author can update their NotificationsRead".into(),
+        ));
+    }
+
+    if notifications_read.last_read_timestamp < original_notifications_read.last_read_timestamp {
+        return Ok(ValidateCallbackResult::Invalid(
+            "NotificationsRead cannot move back in time".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_delete_notifications_read(
+    action: Delete,
+    original_action: EntryCreationAction,
+    _original_notifications_read: NotificationsRead,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != *original_action.author() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the original action author can delete their NotificationsRead".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
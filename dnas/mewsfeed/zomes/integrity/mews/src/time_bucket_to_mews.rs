@@ -0,0 +1,138 @@
+use hdi::prelude::*;
+use hdk::prelude::{Component, Path};
+
+/// Number of path components below a time bucket root: year, month, day, hour
+pub const TIME_BUCKET_DEPTH: usize = 4;
+
+pub fn make_all_mews_time_bucket_root() -> Path {
+    Path::from("all_mews_by_time")
+}
+
+pub fn make_agent_mews_time_bucket_root(agent: AgentPubKey) -> Path {
+    Path::from(vec![
+        Component::from("agent_mews_by_time"),
+        Component::from(agent.to_string()),
+    ])
+}
+
+pub fn make_time_bucket_path(mut root: Path, timestamp: Timestamp) -> Path {
+    for (i, value) in time_bucket_for_timestamp(timestamp).into_iter().enumerate() {
+        let component = match i {
+            0 => value.to_string(),
+            _ => format!("{:02}", value),
+        };
+        root.append_component(Component::from(component));
+    }
+
+    root
+}
+
+/// UTC [year, month, day, hour] of a timestamp
+pub fn time_bucket_for_timestamp(timestamp: Timestamp) -> Vec<u32> {
+    let seconds = timestamp.as_micros().div_euclid(1_000_000);
+    let days = seconds.div_euclid(86_400);
+    let hour = seconds.rem_euclid(86_400) / 3_600;
+
+    // Convert days since unix epoch to a civil date
+    // See http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = match mp < 10 {
+        true => mp + 3,
+        false => mp - 9,
+    };
+    let year = match month <= 2 {
+        true => yoe + era * 400 + 1,
+        false => yoe + era * 400,
+    };
+
+    vec![year as u32, month as u32, day as u32, hour as u32]
+}
+
+pub fn validate_create_link_time_bucket(
+    _action: CreateLink,
+    _base_address: AnyLinkableHash,
+    _target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_delete_link_time_bucket(
+    _action: DeleteLink,
+    _original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "TimeBucket links cannot be deleted",
+    )))
+}
+
+pub fn validate_create_link_time_bucket_to_mews(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash = ActionHash::try_from(target_address).map_err(|err| wasm_error!(err))?;
+    let record = must_get_valid_record(action_hash)?;
+    let _mew: crate::Mew = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Linked action must reference an entry"
+        ))))?;
+
+    if action.author != *record.action().author() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the mew author can create their TimeBucketToMews links".into(),
+        ));
+    }
+
+    // Base address should be the bucket path matching the mew's action timestamp
+    let mew_timestamp = record.action().timestamp();
+    let all_mews_bucket_hash =
+        make_time_bucket_path(make_all_mews_time_bucket_root(), mew_timestamp).path_entry_hash()?;
+    let agent_mews_bucket_hash = make_time_bucket_path(
+        make_agent_mews_time_bucket_root(action.author),
+        mew_timestamp,
+    )
+    .path_entry_hash()?;
+
+    let base_address_entry_hash =
+        EntryHash::try_from(base_address).map_err(|err| wasm_error!(err))?;
+    if base_address_entry_hash != all_mews_bucket_hash
+        && base_address_entry_hash != agent_mews_bucket_hash
+    {
+        return Ok(ValidateCallbackResult::Invalid(
+            "TimeBucketToMews base address must be the time bucket of the mew's action timestamp"
+                .into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_delete_link_time_bucket_to_mews(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != original_action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the original action author can delete their TimeBucketToMews link".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
@@ -0,0 +1,254 @@
+use crate::{get_original_mew_hash, make_time_bucket_path, LinkTypes, Mew, NotificationType};
+use hc_link_type_utils::get_zome_index;
+use hdi::prelude::*;
+use hdk::prelude::{Component, Path};
+
+pub fn make_agent_notifications_time_bucket_root(agent: AgentPubKey) -> Path {
+    Path::from(vec![
+        Component::from("agent_notifications_by_time"),
+        Component::from(agent.to_string()),
+    ])
+}
+
+/// Tag of TimeBucketToNotifications links, describing the notification so that listing them
+/// doesn't fetch anything else
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq, Eq)]
+pub struct NotificationTag {
+    pub notification_type: NotificationType,
+    /// The action the notification is about, such as the like link or the response link
+    pub action_hash: ActionHash,
+    pub feed_mew_hash: Option<ActionHash>,
+    /// The mew that the feed mew responds to, so that responses in muted yarns can be left out
+    pub original_mew_hash: Option<ActionHash>,
+    /// The recipient's own response to the same mew, for FollowedYarnResponded
+    pub yarn_response_hash: Option<ActionHash>,
+}
+
+impl TryFrom<LinkTag> for NotificationTag {
+    type Error = WasmError;
+
+    fn try_from(tag: LinkTag) -> Result<Self, Self::Error> {
+        NotificationTag::try_from(SerializedBytes::from(UnsafeBytes::from(tag.into_inner())))
+            .map_err(|err| wasm_error!(err))
+    }
+}
+
+pub fn validate_create_link_time_bucket_to_notifications(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let notification_tag = match NotificationTag::try_from(tag) {
+        Ok(notification_tag) => notification_tag,
+        Err(_) => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Malformed notification tag".into(),
+            ))
+        }
+    };
+    let recipient =
+        AgentPubKey::from(EntryHash::try_from(target_address).map_err(|err| wasm_error!(err))?);
+    if recipient == action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Agents cannot notify themselves".into(),
+        ));
+    }
+
+    let notified_action = must_get_action(notification_tag.action_hash.clone())?;
+    if *notified_action.action().author() != action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the author of an action can notify others about it".into(),
+        ));
+    }
+
+    let validation =
+        validate_notified_action(notified_action.action(), &notification_tag, &recipient)?;
+    if validation != ValidateCallbackResult::Valid {
+        return Ok(validation);
+    }
+
+    // Base address should be the recipient's bucket matching the notified action's timestamp
+    let bucket_hash = make_time_bucket_path(
+        make_agent_notifications_time_bucket_root(recipient),
+        notified_action.action().timestamp(),
+    )
+    .path_entry_hash()?;
+    if EntryHash::try_from(base_address).map_err(|err| wasm_error!(err))? != bucket_hash {
+        return Ok(ValidateCallbackResult::Invalid(
+            "TimeBucketToNotifications base address must be the recipient's time bucket of the notified action's timestamp".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Check that the notified action is the link creation or deletion its notification type is about,
+/// and that it concerns the recipient
+fn validate_notified_action(
+    notified_action: &Action,
+    notification_tag: &NotificationTag,
+    recipient: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    let (is_delete, link) = match notified_action {
+        Action::CreateLink(create_link) => (false, create_link.clone()),
+        Action::DeleteLink(delete_link) => {
+            match must_get_action(delete_link.link_add_address.clone())?.action() {
+                Action::CreateLink(create_link) => (true, create_link.clone()),
+                _ => {
+                    return Ok(ValidateCallbackResult::Invalid(
+                        "Notified link deletion must delete a link".into(),
+                    ))
+                }
+            }
+        }
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Notifications must be about a link".into(),
+            ))
+        }
+    };
+
+    // The feed mew's original, if any, is kept in the tag
+    let feed_mew = match &notification_tag.feed_mew_hash {
+        Some(feed_mew_hash) => {
+            let record = must_get_valid_record(feed_mew_hash.clone())?;
+            let mew: Mew = match record.entry().to_app_option().map_err(|e| wasm_error!(e))? {
+                Some(mew) => mew,
+                None => {
+                    return Ok(ValidateCallbackResult::Invalid(
+                        "Notification feed mew must be a mew".into(),
+                    ))
+                }
+            };
+            Some((feed_mew_hash.clone(), record.action().author().clone(), mew))
+        }
+        None => None,
+    };
+    if notification_tag.original_mew_hash
+        != feed_mew
+            .as_ref()
+            .and_then(|(_, _, mew)| get_original_mew_hash(mew))
+    {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Notification original mew must be the one the feed mew refers to".into(),
+        ));
+    }
+
+    let recipient_hash = AnyLinkableHash::from(recipient.clone());
+    let author_hash = AnyLinkableHash::from(notified_action.author().clone());
+    let is_mews_link = |link_type: LinkTypes| -> ExternResult<bool> {
+        let scoped_type: ScopedLinkType = link_type.try_into()?;
+        Ok(link.zome_index == scoped_type.zome_index && link.link_type == scoped_type.zome_type)
+    };
+    let is_valid = match notification_tag.notification_type {
+        // Reaction links go from the mew to the agent reacting to it
+        NotificationType::MyMewLicked
+        | NotificationType::MyMewUnlicked
+        | NotificationType::MyMewPinned
+        | NotificationType::MyMewUnpinned => {
+            let (zome_name, notifies_delete) = match notification_tag.notification_type {
+                NotificationType::MyMewLicked => ("likes_integrity", false),
+                NotificationType::MyMewUnlicked => ("likes_integrity", true),
+                NotificationType::MyMewPinned => ("agent_pins_integrity", false),
+                _ => ("agent_pins_integrity", true),
+            };
+            match &feed_mew {
+                Some((feed_mew_hash, feed_mew_author, _)) => {
+                    is_delete == notifies_delete
+                        && link.zome_index == get_zome_index(zome_name)?
+                        && link.base_address == AnyLinkableHash::from(feed_mew_hash.clone())
+                        && link.target_address == author_hash
+                        && feed_mew_author == recipient
+                }
+                None => false,
+            }
+        }
+        NotificationType::MyMewResponded | NotificationType::FollowedYarnResponded => {
+            match (&feed_mew, &notification_tag.original_mew_hash) {
+                (Some((feed_mew_hash, _, _)), Some(original_mew_hash))
+                    if !is_delete
+                        && is_mews_link(LinkTypes::MewToResponses)?
+                        && link.base_address
+                            == AnyLinkableHash::from(original_mew_hash.clone())
+                        && link.target_address == AnyLinkableHash::from(feed_mew_hash.clone()) =>
+                {
+                    if notification_tag.notification_type == NotificationType::MyMewResponded {
+                        must_get_action(original_mew_hash.clone())?
+                            .action()
+                            .author()
+                            == recipient
+                    } else {
+                        is_yarn_response(
+                            &notification_tag.yarn_response_hash,
+                            original_mew_hash,
+                            recipient,
+                        )?
+                    }
+                }
+                _ => false,
+            }
+        }
+        NotificationType::MyAgentMentioned => match &feed_mew {
+            Some((feed_mew_hash, _, _)) => {
+                !is_delete
+                    && is_mews_link(LinkTypes::MentionToMews)?
+                    && link.base_address == recipient_hash
+                    && link.target_address == AnyLinkableHash::from(feed_mew_hash.clone())
+            }
+            None => false,
+        },
+        // CreatorToFollowers links go from the creator to the follower
+        NotificationType::MyAgentFollowed | NotificationType::MyAgentUnfollowed => {
+            feed_mew.is_none()
+                && is_delete
+                    == (notification_tag.notification_type == NotificationType::MyAgentUnfollowed)
+                && link.zome_index == get_zome_index("follows_integrity")?
+                && link.base_address == recipient_hash
+                && link.target_address == author_hash
+        }
+        // Follow requests and acceptances are only pushed as signals
+        NotificationType::MyAgentFollowRequested | NotificationType::MyFollowRequestAccepted => {
+            false
+        }
+    };
+    if !is_valid {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Notified action doesn't match the notification type and recipient".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Whether the recipient wrote the given response to the mew, following its yarn
+fn is_yarn_response(
+    yarn_response_hash: &Option<ActionHash>,
+    responded_mew_hash: &ActionHash,
+    recipient: &AgentPubKey,
+) -> ExternResult<bool> {
+    let yarn_response_hash = match yarn_response_hash {
+        Some(yarn_response_hash) => yarn_response_hash.clone(),
+        None => return Ok(false),
+    };
+    let record = must_get_valid_record(yarn_response_hash)?;
+    let response: Option<Mew> = record.entry().to_app_option().map_err(|e| wasm_error!(e))?;
+
+    Ok(record.action().author() == recipient
+        && response
+            .and_then(|response| get_original_mew_hash(&response))
+            .as_ref()
+            == Some(responded_mew_hash))
+}
+
+pub fn validate_delete_link_time_bucket_to_notifications(
+    _action: DeleteLink,
+    _original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "TimeBucketToNotifications links cannot be deleted",
+    )))
+}
@@ -44,7 +44,9 @@ export const mewsfeedAppBundleSource: AppBundleSource =
   createMewsFeedAppBundleSource({
     mew_characters_min: 5,
     mew_characters_max: 200,
+    mew_edit_window_seconds: 60,
     prefix_index_width: 3,
+    max_pins: 3,
   });
 
 export const mewsfeedAppBundleSourceNoLengthLimits: AppBundleSource =
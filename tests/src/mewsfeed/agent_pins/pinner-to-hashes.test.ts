@@ -1,7 +1,8 @@
-import { assert, test } from "vitest";
+import { assert, expect, test } from "vitest";
 
 import { runScenario, dhtSync } from "@holochain/tryorama";
 import { AgentPubKey, HoloHash, fakeActionHash } from "@holochain/client";
+import { mewsfeedAppBundleSource } from "../../common";
 
 test("link a Pinner to a Hash", async () => {
   await runScenario(
@@ -90,3 +91,74 @@ test("link a Pinner to a Hash", async () => {
     { timeout: 500000 }
   );
 });
+
+test("Pins are capped by max_pins and kept in the pinner's order", async () => {
+  await runScenario(
+    async (scenario) => {
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+      const [alice, bob] = await scenario.addPlayersWithApps([
+        appSource,
+        appSource,
+      ]);
+      await scenario.shareAllAgents();
+
+      const hashes = [
+        await fakeActionHash(),
+        await fakeActionHash(),
+        await fakeActionHash(),
+      ];
+      for (const hash of hashes) {
+        await alice.cells[0].callZome({
+          zome_name: "agent_pins",
+          fn_name: "pin_hash",
+          payload: hash,
+        });
+      }
+
+      // A pin beyond max_pins is rejected
+      const response = alice.cells[0].callZome({
+        zome_name: "agent_pins",
+        fn_name: "pin_hash",
+        payload: await fakeActionHash(),
+      });
+      await expect(response).rejects.toHaveProperty(
+        "data.data",
+        expect.stringContaining("InvalidCommit")
+      );
+
+      // Pins are in the order they were made
+      let pins: HoloHash[] = await alice.cells[0].callZome({
+        zome_name: "agent_pins",
+        fn_name: "get_pins_ordered",
+        payload: alice.agentPubKey,
+      });
+      assert.deepEqual(pins, hashes);
+
+      // Reordering must keep the same pins
+      const invalidReorder = alice.cells[0].callZome({
+        zome_name: "agent_pins",
+        fn_name: "reorder_pins",
+        payload: [hashes[0], hashes[0], hashes[1]],
+      });
+      await expect(invalidReorder).rejects.toThrow();
+
+      const newOrder = [hashes[2], hashes[0], hashes[1]];
+      await alice.cells[0].callZome({
+        zome_name: "agent_pins",
+        fn_name: "reorder_pins",
+        payload: newOrder,
+      });
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      pins = await bob.cells[0].callZome({
+        zome_name: "agent_pins",
+        fn_name: "get_pins_ordered",
+        payload: alice.agentPubKey,
+      });
+      assert.deepEqual(pins, newOrder);
+    },
+    true,
+    { timeout: 500000 }
+  );
+});
@@ -0,0 +1,183 @@
+import { ActionHash, AgentPubKey } from "@holochain/client";
+import { dhtSync, runScenario } from "@holochain/tryorama";
+import { assert, expect, test } from "vitest";
+import {
+  FeedMew,
+  LinkTargetName,
+  Notification,
+  NotificationTypeName,
+} from "../../../../ui/src/types/types";
+import { mewsfeedAppBundleSource } from "../../common";
+import { createMew } from "../mews/common";
+
+test("blocked agents are left out of my feeds and notifications", async () => {
+  await runScenario(
+    async (scenario) => {
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+      const [alice, bob, carol] = await scenario.addPlayersWithApps([
+        appSource,
+        appSource,
+        appSource,
+      ]);
+      await scenario.shareAllAgents();
+
+      // Bob and Carol both mention Alice
+      const bobMewHash: ActionHash = await createMew(bob.cells[0], {
+        text: "this is for @alice from bob",
+        links: [{ [LinkTargetName.Mention]: alice.agentPubKey }],
+        mew_type: { Original: null },
+      });
+      const carolMewHash: ActionHash = await createMew(carol.cells[0], {
+        text: "this is for @alice from carol",
+        links: [{ [LinkTargetName.Mention]: alice.agentPubKey }],
+        mew_type: { Original: null },
+      });
+
+      await dhtSync([alice, bob, carol], alice.cells[0].cell_id[0]);
+
+      // Alice blocks Bob
+      await alice.cells[0].callZome({
+        zome_name: "blocks",
+        fn_name: "block_agent",
+        payload: bob.agentPubKey,
+      });
+      const blockedAgents: AgentPubKey[] = await alice.cells[0].callZome({
+        zome_name: "blocks",
+        fn_name: "get_my_blocked_agents",
+        payload: null,
+      });
+      expect(blockedAgents).toEqual([bob.agentPubKey]);
+
+      // Alice cannot block herself
+      await expect(
+        alice.cells[0].callZome({
+          zome_name: "blocks",
+          fn_name: "block_agent",
+          payload: alice.agentPubKey,
+        })
+      ).rejects.toThrow();
+
+      // Bob's mention is left out of Alice's mention feed and notifications
+      const mentionedMews: FeedMew[] = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_mews_for_mention_with_context",
+        payload: { mention: alice.agentPubKey },
+      });
+      expect(mentionedMews.map((feedMew) => feedMew.action_hash)).toEqual([
+        carolMewHash,
+      ]);
+
+      const notifications: Notification[] = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_my_notifications",
+        payload: null,
+      });
+      expect(notifications.length).toEqual(1);
+      expect(notifications[0]).toMatchObject({
+        agent: carol.agentPubKey,
+        notification_type: { [NotificationTypeName.MyAgentMentioned]: null },
+      });
+
+      // Bob still sees his own mew
+      const bobMentionedMews: FeedMew[] = await bob.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_mews_for_mention_with_context",
+        payload: { mention: alice.agentPubKey },
+      });
+      assert.equal(bobMentionedMews.length, 2);
+
+      // Once unblocked, Bob's mention is back
+      await alice.cells[0].callZome({
+        zome_name: "blocks",
+        fn_name: "unblock_agent",
+        payload: bob.agentPubKey,
+      });
+      const unblockedMentionedMews: FeedMew[] = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_mews_for_mention_with_context",
+        payload: { mention: alice.agentPubKey },
+      });
+      expect(
+        unblockedMentionedMews.map((feedMew) => feedMew.action_hash)
+      ).toContainEqual(bobMewHash);
+    },
+    true,
+    { timeout: 500000 }
+  );
+});
+
+test("muted agents are left out of my feeds, agents muted for notifications are not", async () => {
+  await runScenario(
+    async (scenario) => {
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+      const [alice, bob, carol] = await scenario.addPlayersWithApps([
+        appSource,
+        appSource,
+        appSource,
+      ]);
+      await scenario.shareAllAgents();
+
+      // Bob and Carol both mention Alice
+      const bobMewHash: ActionHash = await createMew(bob.cells[0], {
+        text: "this is for @alice from bob",
+        links: [{ [LinkTargetName.Mention]: alice.agentPubKey }],
+        mew_type: { Original: null },
+      });
+      const carolMewHash: ActionHash = await createMew(carol.cells[0], {
+        text: "this is for @alice from carol",
+        links: [{ [LinkTargetName.Mention]: alice.agentPubKey }],
+        mew_type: { Original: null },
+      });
+
+      await dhtSync([alice, bob, carol], alice.cells[0].cell_id[0]);
+
+      // Alice mutes Bob in her feeds and Carol in her notifications only
+      await alice.cells[0].callZome({
+        zome_name: "blocks",
+        fn_name: "mute_agent",
+        payload: bob.agentPubKey,
+      });
+      await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "set_my_notification_preferences",
+        payload: {
+          disabled_types: [],
+          muted_agents: [carol.agentPubKey],
+          muted_yarns: [],
+        },
+      });
+      const mutedAgents: AgentPubKey[] = await alice.cells[0].callZome({
+        zome_name: "blocks",
+        fn_name: "get_my_muted_agents",
+        payload: null,
+      });
+      expect(mutedAgents).toEqual([bob.agentPubKey]);
+
+      const mentionedMews: FeedMew[] = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_mews_for_mention_with_context",
+        payload: { mention: alice.agentPubKey },
+      });
+      expect(mentionedMews.map((feedMew) => feedMew.action_hash)).toEqual([
+        carolMewHash,
+      ]);
+
+      // Once unmuted, Bob's mention is back
+      await alice.cells[0].callZome({
+        zome_name: "blocks",
+        fn_name: "unmute_agent",
+        payload: bob.agentPubKey,
+      });
+      const unmutedMentionedMews: FeedMew[] = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_mews_for_mention_with_context",
+        payload: { mention: alice.agentPubKey },
+      });
+      expect(
+        unmutedMentionedMews.map((feedMew) => feedMew.action_hash)
+      ).toContainEqual(bobMewHash);
+    },
+    true,
+    { timeout: 500000 }
+  );
+});
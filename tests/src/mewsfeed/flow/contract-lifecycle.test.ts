@@ -0,0 +1,146 @@
+import { assert, test, expect } from "vitest";
+import { runScenario, dhtSync } from "@holochain/tryorama";
+import { ActionHash, Record } from "@holochain/client";
+import { mewsfeedAppBundleSource } from "../../common";
+import { LinkTargetName, MewTypeName } from "../../../../ui/src/types/types.js";
+
+test("A contract goes from requested to thanked and is recorded as a job done", async () => {
+  await runScenario(
+    async (scenario) => {
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+      const [alice, bob] = await scenario.addPlayersWithApps([
+        appSource,
+        appSource,
+      ]);
+      await scenario.shareAllAgents();
+
+      // Alice requests a coffee from Bob
+      const requestHash: ActionHash = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "create_mew",
+        payload: {
+          text: "%Request: @bob $1.5BTC Make me a coffee",
+          links: [{ [LinkTargetName.Mention]: bob.agentPubKey }],
+          mew_type: { [MewTypeName.Original]: null },
+        },
+      });
+      const requested = await alice.cells[0].callZome({
+        zome_name: "flow",
+        fn_name: "process_thread",
+        payload: [requestHash],
+      });
+      assert.equal(requested.contracts.length, 1);
+      const contractHash: ActionHash = requested.contracts[0];
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      // Bob cannot deliver before promising
+      await expect(
+        bob.cells[0].callZome({
+          zome_name: "flow",
+          fn_name: "update_contract_status",
+          payload: { contract_hash: contractHash, status: "Delivered" },
+        })
+      ).rejects.toThrow();
+
+      // Bob's promise moves the requested contract to promised
+      const promiseHash: ActionHash = await bob.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "create_mew",
+        payload: {
+          text: "%Promise: @alice I'm on it!",
+          links: [],
+          mew_type: { [MewTypeName.Reply]: requestHash },
+        },
+      });
+      await bob.cells[0].callZome({
+        zome_name: "flow",
+        fn_name: "process_thread",
+        payload: [requestHash, promiseHash],
+      });
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      const contracts: Record[] = await alice.cells[0].callZome({
+        zome_name: "flow",
+        fn_name: "get_contracts_for_request",
+        payload: requestHash,
+      });
+      assert.equal(contracts.length, 1);
+
+      // Only the promiser can mark the contract delivered
+      await expect(
+        alice.cells[0].callZome({
+          zome_name: "flow",
+          fn_name: "update_contract_status",
+          payload: { contract_hash: contractHash, status: "Delivered" },
+        })
+      ).rejects.toThrow();
+      await bob.cells[0].callZome({
+        zome_name: "flow",
+        fn_name: "update_contract_status",
+        payload: { contract_hash: contractHash, status: "Delivered" },
+      });
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      // A delivered contract can no longer be cancelled
+      await expect(
+        alice.cells[0].callZome({
+          zome_name: "flow",
+          fn_name: "update_contract_status",
+          payload: { contract_hash: contractHash, status: "Cancelled" },
+        })
+      ).rejects.toThrow();
+
+      // Alice's thanks moves the contract to thanked
+      const thanksHash: ActionHash = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "create_mew",
+        payload: {
+          text: "%Thanks: @bob Loved your work",
+          links: [],
+          mew_type: { [MewTypeName.Reply]: promiseHash },
+        },
+      });
+      await alice.cells[0].callZome({
+        zome_name: "flow",
+        fn_name: "process_thread",
+        payload: [requestHash, promiseHash, thanksHash],
+      });
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      const jobsDone: Record[] = await bob.cells[0].callZome({
+        zome_name: "flow",
+        fn_name: "get_jobs_done_for_promiser",
+        payload: bob.agentPubKey,
+      });
+      assert.equal(jobsDone.length, 1);
+
+      const reputation = await alice.cells[0].callZome({
+        zome_name: "flow",
+        fn_name: "get_flow_reputation",
+        payload: bob.agentPubKey,
+      });
+      assert.deepEqual(reputation, {
+        promised: 1,
+        delivered: 1,
+        thanked: 1,
+        expired: 0,
+        totals: { BTC: "1.5" },
+      });
+
+      // Thanked is final
+      await expect(
+        bob.cells[0].callZome({
+          zome_name: "flow",
+          fn_name: "update_contract_status",
+          payload: { contract_hash: contractHash, status: "Expired" },
+        })
+      ).rejects.toThrow();
+    },
+    true,
+    { timeout: 500000 }
+  );
+});
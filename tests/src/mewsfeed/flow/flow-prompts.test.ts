@@ -0,0 +1,117 @@
+import { assert, test } from "vitest";
+import { runScenario, dhtSync } from "@holochain/tryorama";
+import { mewsfeedAppBundleSource } from "../../common";
+import {
+  FlowPrompt,
+  FlowPromptKindName,
+  MewTypeName,
+} from "../../../../ui/src/types/types.js";
+
+test("prompt_for_next_flow suggests kinds, agents by nickname and date ranges", async () => {
+  await runScenario(
+    async (scenario) => {
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+      const [alice, bob] = await scenario.addPlayersWithApps([
+        appSource,
+        appSource,
+      ]);
+      await scenario.shareAllAgents();
+
+      await alice.cells[0].callZome({
+        zome_name: "profiles",
+        fn_name: "create_profile",
+        payload: { nickname: "alice", fields: {} },
+      });
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      const kindPrompts: FlowPrompt[] = await bob.cells[0].callZome({
+        zome_name: "flow",
+        fn_name: "prompt_for_next_flow",
+        payload: "%Req",
+      });
+      assert.deepEqual(
+        kindPrompts.map((prompt) => prompt.insert_text),
+        ["%Request:"]
+      );
+
+      const mentionPrompts: FlowPrompt[] = await bob.cells[0].callZome({
+        zome_name: "flow",
+        fn_name: "prompt_for_next_flow",
+        payload: "%Request: @ali",
+      });
+      assert.equal(mentionPrompts.length, 1);
+      assert.equal(mentionPrompts[0].insert_text, "@alice");
+      assert.deepEqual(mentionPrompts[0].kind, {
+        [FlowPromptKindName.Mention]: alice.agentPubKey,
+      });
+
+      const betweenPrompts: FlowPrompt[] = await bob.cells[0].callZome({
+        zome_name: "flow",
+        fn_name: "prompt_for_next_flow",
+        payload: "%Request: @alice betw",
+      });
+      assert.equal(betweenPrompts.length, 1);
+      assert.match(
+        betweenPrompts[0].insert_text,
+        /^between \d{4}\/\d{2}\/\d{2}-\d{4}\/\d{2}\/\d{2}$/
+      );
+
+      // Thanks have no date range
+      const thanksPrompts: FlowPrompt[] = await bob.cells[0].callZome({
+        zome_name: "flow",
+        fn_name: "prompt_for_next_flow",
+        payload: "%Thanks: @alice betw",
+      });
+      assert.equal(thanksPrompts.length, 0);
+    },
+    true,
+    { timeout: 500000 }
+  );
+});
+
+test("prompt_for_next_flow suggests amounts in indexed cashtags", async () => {
+  await runScenario(
+    async (scenario) => {
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+      const [alice, bob] = await scenario.addPlayersWithApps([
+        appSource,
+        appSource,
+      ]);
+      await scenario.shareAllAgents();
+
+      await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "create_mew",
+        payload: {
+          text: "Who else is stacking $BTC?",
+          links: [],
+          mew_type: { [MewTypeName.Original]: null },
+        },
+      });
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      const cashtagPrompts: FlowPrompt[] = await bob.cells[0].callZome({
+        zome_name: "flow",
+        fn_name: "prompt_for_next_flow",
+        payload: "%Request: @alice $1BTC",
+      });
+      assert.equal(cashtagPrompts.length, 1);
+      assert.equal(cashtagPrompts[0].insert_text, "$1BTC");
+      assert.deepEqual(cashtagPrompts[0].kind, {
+        [FlowPromptKindName.Cashtag]: null,
+      });
+
+      // Thanks have no amount
+      const thanksPrompts: FlowPrompt[] = await bob.cells[0].callZome({
+        zome_name: "flow",
+        fn_name: "prompt_for_next_flow",
+        payload: "%Thanks: @alice $1BTC",
+      });
+      assert.equal(thanksPrompts.length, 0);
+    },
+    true,
+    { timeout: 500000 }
+  );
+});
@@ -0,0 +1,138 @@
+import { assert, test } from "vitest";
+import { runScenario, dhtSync } from "@holochain/tryorama";
+import { ActionHash, Record } from "@holochain/client";
+import { mewsfeedAppBundleSource } from "../../common";
+import { MewTypeName } from "../../../../ui/src/types/types.js";
+
+test("process_thread parses flows and commits a contract for a promise", async () => {
+  await runScenario(
+    async (scenario) => {
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+      const [alice, bob] = await scenario.addPlayersWithApps([
+        appSource,
+        appSource,
+      ]);
+      await scenario.shareAllAgents();
+
+      const requestHash: ActionHash = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "create_mew",
+        payload: {
+          text: "%Request: @bob $1.5BTC between 2099/05/01-2099/05/06 Make me a coffee",
+          links: [],
+          mew_type: { [MewTypeName.Original]: null },
+        },
+      });
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      const promiseHash: ActionHash = await bob.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "create_mew",
+        payload: {
+          text: "%Promise: @alice I'm on it!",
+          links: [],
+          mew_type: { [MewTypeName.Reply]: requestHash },
+        },
+      });
+      const malformedHash: ActionHash = await bob.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "create_mew",
+        payload: {
+          text: "%Request: $BTC please",
+          links: [],
+          mew_type: { [MewTypeName.Reply]: requestHash },
+        },
+      });
+
+      const processed = await bob.cells[0].callZome({
+        zome_name: "flow",
+        fn_name: "process_thread",
+        payload: [requestHash, promiseHash, malformedHash],
+      });
+
+      assert.equal(processed.flows.length, 3);
+      assert.deepEqual(processed.flows[0].flow.Ok, {
+        kind: "Request",
+        mention: "@bob",
+        amount: { value: "1.5", currency: "BTC" },
+        between: { start: "2099-05-01", end: "2099-05-06" },
+        text: "Make me a coffee",
+      });
+      assert.equal(processed.flows[1].flow.Ok.kind, "Promise");
+      assert.equal(processed.flows[2].flow.Err.position, 11);
+      assert.equal(processed.contracts.length, 1);
+
+      // Processing the yarn again doesn't commit another contract
+      const processedAgain = await bob.cells[0].callZome({
+        zome_name: "flow",
+        fn_name: "process_thread",
+        payload: [requestHash, promiseHash],
+      });
+      assert.deepEqual(processedAgain.contracts, processed.contracts);
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      const contracts: Record[] = await alice.cells[0].callZome({
+        zome_name: "flow",
+        fn_name: "get_contracts_for_request",
+        payload: requestHash,
+      });
+      assert.equal(contracts.length, 1);
+    },
+    true,
+    { timeout: 500000 }
+  );
+});
+
+test("A promise after the request's between dates commits no contract", async () => {
+  await runScenario(
+    async (scenario) => {
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+      const [alice, bob] = await scenario.addPlayersWithApps([
+        appSource,
+        appSource,
+      ]);
+      await scenario.shareAllAgents();
+
+      const requestHash: ActionHash = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "create_mew",
+        payload: {
+          text: "%Request: between 2023/05/01-2023/05/06 Make me a coffee",
+          links: [],
+          mew_type: { [MewTypeName.Original]: null },
+        },
+      });
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      const promiseHash: ActionHash = await bob.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "create_mew",
+        payload: {
+          text: "%Promise: @alice I'm on it!",
+          links: [],
+          mew_type: { [MewTypeName.Reply]: requestHash },
+        },
+      });
+
+      const processed = await bob.cells[0].callZome({
+        zome_name: "flow",
+        fn_name: "process_thread",
+        payload: [requestHash, promiseHash],
+      });
+      assert.equal(processed.flows.length, 2);
+      assert.equal(processed.contracts.length, 0);
+
+      const overdue: Record[] = await alice.cells[0].callZome({
+        zome_name: "flow",
+        fn_name: "get_overdue_contracts",
+        payload: bob.agentPubKey,
+      });
+      assert.equal(overdue.length, 0);
+    },
+    true,
+    { timeout: 500000 }
+  );
+});
@@ -0,0 +1,104 @@
+import { assert, test, expect } from "vitest";
+import { runScenario, dhtSync } from "@holochain/tryorama";
+import { ActionHash, AgentPubKey, Record } from "@holochain/client";
+import { mewsfeedAppBundleSource } from "../../common";
+
+test("protected accounts can only be followed once a follow request is accepted", async () => {
+  await runScenario(
+    async (scenario) => {
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+      const [alice, bob] = await scenario.addPlayersWithApps([
+        appSource,
+        appSource,
+      ]);
+      await scenario.shareAllAgents();
+
+      // Alice protects her account
+      await alice.cells[0].callZome({
+        zome_name: "profiles",
+        fn_name: "create_profile",
+        payload: { nickname: "alice", fields: { protected: "true" } },
+      });
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      // Bob cannot follow Alice directly
+      await expect(
+        bob.cells[0].callZome({
+          zome_name: "follows",
+          fn_name: "follow",
+          payload: alice.agentPubKey,
+        })
+      ).rejects.toThrow();
+
+      // Bob requests to follow Alice
+      const followRequestHash: ActionHash = await bob.cells[0].callZome({
+        zome_name: "follows",
+        fn_name: "request_to_follow",
+        payload: alice.agentPubKey,
+      });
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      const followRequests: Record[] = await alice.cells[0].callZome({
+        zome_name: "follows",
+        fn_name: "get_my_follow_requests",
+        payload: null,
+      });
+      assert.equal(followRequests.length, 1);
+      assert.deepEqual(
+        followRequests[0].signed_action.hashed.hash,
+        followRequestHash
+      );
+
+      // Bob cannot answer his own request
+      await expect(
+        bob.cells[0].callZome({
+          zome_name: "follows",
+          fn_name: "accept_follow_request",
+          payload: followRequestHash,
+        })
+      ).rejects.toThrow();
+
+      // Alice accepts and the request is no longer pending
+      await alice.cells[0].callZome({
+        zome_name: "follows",
+        fn_name: "accept_follow_request",
+        payload: followRequestHash,
+      });
+      const remainingFollowRequests: Record[] = await alice.cells[0].callZome({
+        zome_name: "follows",
+        fn_name: "get_my_follow_requests",
+        payload: null,
+      });
+      assert.equal(remainingFollowRequests.length, 0);
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      // Bob can now follow Alice
+      await bob.cells[0].callZome({
+        zome_name: "follows",
+        fn_name: "follow",
+        payload: alice.agentPubKey,
+      });
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      const followers: AgentPubKey[] = await alice.cells[0].callZome({
+        zome_name: "follows",
+        fn_name: "get_followers_for_creator",
+        payload: { creator: alice.agentPubKey },
+      });
+      assert.deepEqual(followers, [bob.agentPubKey]);
+
+      const visibleAgents: AgentPubKey[] = await bob.cells[0].callZome({
+        zome_name: "follows",
+        fn_name: "get_visible_agents",
+        payload: [alice.agentPubKey],
+      });
+      assert.deepEqual(visibleAgents, [alice.agentPubKey]);
+    },
+    true,
+    { timeout: 500000 }
+  );
+});
@@ -131,6 +131,68 @@ test("Agent cannot follow themselves", async () => {
   );
 });
 
+test("Agent cannot follow the same creator twice", async () => {
+  await runScenario(
+    async (scenario) => {
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+      const [alice, bob] = await scenario.addPlayersWithApps([
+        appSource,
+        appSource,
+      ]);
+      await scenario.shareAllAgents();
+
+      await bob.cells[0].callZome({
+        zome_name: "follows",
+        fn_name: "follow",
+        payload: alice.agentPubKey,
+      });
+
+      // Bob tries to follow Alice again
+      const response = bob.cells[0].callZome({
+        zome_name: "follows",
+        fn_name: "follow",
+        payload: alice.agentPubKey,
+      });
+      await expect(response).rejects.toHaveProperty(
+        "data.data",
+        expect.stringContaining("InvalidCommit")
+      );
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      const followersCount: number = await alice.cells[0].callZome({
+        zome_name: "follows",
+        fn_name: "count_followers_for_creator",
+        payload: alice.agentPubKey,
+      });
+      assert.equal(followersCount, 1);
+
+      // Following again after unfollowing is allowed
+      await bob.cells[0].callZome({
+        zome_name: "follows",
+        fn_name: "unfollow",
+        payload: alice.agentPubKey,
+      });
+      await bob.cells[0].callZome({
+        zome_name: "follows",
+        fn_name: "follow",
+        payload: alice.agentPubKey,
+      });
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      const followers: AgentPubKey[] = await alice.cells[0].callZome({
+        zome_name: "follows",
+        fn_name: "get_followers_for_creator",
+        payload: { creator: alice.agentPubKey },
+      });
+      assert.deepEqual(followers, [bob.agentPubKey]);
+    },
+    true,
+    { timeout: 500000 }
+  );
+});
+
 test("Agent can only change their own follows", async () => {
   await runScenario(
     async (scenario) => {
@@ -1,6 +1,6 @@
 import { assert, expect, test } from "vitest";
 import { runScenario, dhtSync } from "@holochain/tryorama";
-import { Record, fakeActionHash } from "@holochain/client";
+import { AgentPubKey, Record, fakeActionHash } from "@holochain/client";
 import { mewsfeedAppBundleSource } from "../../common";
 
 test("link a Liker to a Hash", async () => {
@@ -59,6 +59,20 @@ test("link a Liker to a Hash", async () => {
       });
       assert.equal(linksOutput.length, 1);
 
+      // Counts use the link type resolved from the DNA's zome order
+      const likersCount: number = await bob.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "count_likers_for_hash",
+        payload: targetAddress,
+      });
+      assert.equal(likersCount, 1);
+      const isLiker: boolean = await bob.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "is_liker_for_hash",
+        payload: { liker: alice.agentPubKey, hash: targetAddress },
+      });
+      assert.isTrue(isLiker);
+
       await alice.cells[0].callZome({
         zome_name: "likes",
         fn_name: "remove_hash_for_liker",
@@ -160,3 +174,163 @@ test("Agent can only change their own likes", async () => {
     { timeout: 500000 }
   );
 });
+
+test("Agent cannot like the same hash twice", async () => {
+  await runScenario(
+    async (scenario) => {
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+      const [alice, bob] = await scenario.addPlayersWithApps([
+        appSource,
+        appSource,
+      ]);
+      await scenario.shareAllAgents();
+
+      const targetAddress = await fakeActionHash();
+
+      await alice.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "like",
+        payload: targetAddress,
+      });
+
+      // Liking again is a no-op
+      await alice.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "like",
+        payload: targetAddress,
+      });
+
+      // A second like link is rejected
+      const response = alice.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "add_hash_for_liker",
+        payload: {
+          base_liker: alice.agentPubKey,
+          target_hash: targetAddress,
+        },
+      });
+      await expect(response).rejects.toHaveProperty(
+        "data.data",
+        expect.stringContaining("InvalidCommit")
+      );
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      const likersCount: number = await bob.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "count_likers_for_hash",
+        payload: targetAddress,
+      });
+      assert.equal(likersCount, 1);
+
+      // Unliking twice is a no-op, and liking again afterwards is allowed
+      await alice.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "unlike",
+        payload: targetAddress,
+      });
+      await alice.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "unlike",
+        payload: targetAddress,
+      });
+      await alice.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "like",
+        payload: targetAddress,
+      });
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      const likersCountAfterRelike: number = await bob.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "count_likers_for_hash",
+        payload: targetAddress,
+      });
+      assert.equal(likersCountAfterRelike, 1);
+    },
+    true,
+    { timeout: 500000 }
+  );
+});
+
+test("Agents can react with the allowed reactions", async () => {
+  await runScenario(
+    async (scenario) => {
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+      const [alice, bob] = await scenario.addPlayersWithApps([
+        appSource,
+        appSource,
+      ]);
+      await scenario.shareAllAgents();
+
+      const targetAddress = await fakeActionHash();
+
+      await alice.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "like",
+        payload: targetAddress,
+      });
+      await alice.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "react",
+        payload: { hash: targetAddress, reaction: "purr" },
+      });
+      await bob.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "react",
+        payload: { hash: targetAddress, reaction: "purr" },
+      });
+
+      // Reactions outside the allowed set are rejected
+      const response = bob.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "react",
+        payload: { hash: targetAddress, reaction: ":sparkles:" },
+      });
+      await expect(response).rejects.toHaveProperty(
+        "data.data",
+        expect.stringContaining("InvalidCommit")
+      );
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      const reactionCounts = await bob.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "count_reactions_for_hash",
+        payload: targetAddress,
+      });
+      assert.deepEqual(reactionCounts, { lick: 1, purr: 2 });
+
+      const likersCount: number = await bob.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "count_likers_for_hash",
+        payload: targetAddress,
+      });
+      assert.equal(likersCount, 1);
+
+      const lickers: AgentPubKey[] = await bob.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "get_reactors_for_hash",
+        payload: { hash: targetAddress, reaction: "lick" },
+      });
+      assert.deepEqual(lickers, [alice.agentPubKey]);
+
+      const reactors: AgentPubKey[] = await bob.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "get_reactors_for_hash",
+        payload: { hash: targetAddress, reaction: null },
+      });
+      assert.equal(reactors.length, 2);
+
+      const myReactions: string[] = await bob.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "get_my_reactions_for_hash",
+        payload: targetAddress,
+      });
+      assert.deepEqual(myReactions, ["purr"]);
+    },
+    true,
+    { timeout: 500000 }
+  );
+});
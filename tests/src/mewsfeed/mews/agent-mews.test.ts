@@ -1,7 +1,12 @@
 import { ActionHash } from "@holochain/client";
 import { dhtSync, runScenario } from "@holochain/tryorama";
 import { assert, expect, test } from "vitest";
-import { FeedMew, Mew, MewTypeName } from "../../../../ui/src/types/types";
+import {
+  FeedMew,
+  Mew,
+  MewTypeName,
+  Page,
+} from "../../../../ui/src/types/types";
 import { mewsfeedAppBundleSource } from "../../common";
 import { createMew } from "./common";
 
@@ -250,3 +255,94 @@ test("Agent mews list are time-paginated", async () => {
     { timeout: 500000 }
   );
 });
+
+test("Agent mews list cursor pagination resumes after a deleted anchor mew", async () => {
+  await runScenario(
+    async (scenario) => {
+      // Set up the app to be installed
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+
+      // Add 2 players with the test app to the Scenario. The returned players
+      // can be destructured.
+      const [alice] = await scenario.addPlayersWithApps([appSource]);
+
+      // Shortcut peer discovery through gossip and register all agents in every
+      // conductor of the scenario.
+      await scenario.shareAllAgents();
+
+      const mewActionHashes: ActionHash[] = [];
+      for (let i = 1; i <= 5; i++) {
+        const createMewInput: Mew = {
+          text: `My Mew with #hashtag ${i}`,
+          links: [],
+          mew_type: { [MewTypeName.Original]: null },
+        };
+        mewActionHashes.push(
+          await alice.cells[0].callZome({
+            zome_name: "mews",
+            fn_name: "create_mew",
+            payload: createMewInput,
+          })
+        );
+      }
+
+      const page1: Page<FeedMew> = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_agent_mews_page_with_context",
+        payload: {
+          agent: alice.agentPubKey,
+          page: {
+            limit: 2,
+          },
+        },
+      });
+
+      assert.lengthOf(page1.items, 2);
+      assert.isTrue(page1.has_more);
+      assert.deepEqual(page1.items[0].action_hash, mewActionHashes[4]);
+      assert.deepEqual(page1.items[1].action_hash, mewActionHashes[3]);
+
+      // Alice deletes the mew the cursor points at
+      await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "delete_mew",
+        payload: mewActionHashes[3],
+      });
+
+      const page2: Page<FeedMew> = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_agent_mews_page_with_context",
+        payload: {
+          agent: alice.agentPubKey,
+          page: {
+            after_cursor: page1.next_cursor,
+            limit: 2,
+          },
+        },
+      });
+
+      assert.lengthOf(page2.items, 2);
+      assert.isTrue(page2.has_more);
+      assert.deepEqual(page2.items[0].action_hash, mewActionHashes[2]);
+      assert.deepEqual(page2.items[1].action_hash, mewActionHashes[1]);
+
+      const page3: Page<FeedMew> = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_agent_mews_page_with_context",
+        payload: {
+          agent: alice.agentPubKey,
+          page: {
+            after_cursor: page2.next_cursor,
+            limit: 2,
+          },
+        },
+      });
+
+      assert.lengthOf(page3.items, 1);
+      assert.isFalse(page3.has_more);
+      assert.deepEqual(page3.items[0].action_hash, mewActionHashes[0]);
+    },
+    true,
+    { timeout: 500000 }
+  );
+});
@@ -1,10 +1,13 @@
-import { ActionHash } from "@holochain/client";
+import { ActionHash, AppSignal } from "@holochain/client";
 import { dhtSync, runScenario } from "@holochain/tryorama";
 import { assert, expect, test } from "vitest";
 import {
   FeedMew,
+  GroupedNotification,
   Mew,
   MewTypeName,
+  Notification,
+  NotificationPreferences,
   NotificationTypeName,
   PaginationDirectionName,
 } from "../../../../ui/src/types/types";
@@ -753,3 +756,333 @@ test("notifications list is time-paginated", async () => {
     { timeout: 500000 }
   );
 });
+
+test("notifications read state is kept on my source chain", async () => {
+  await runScenario(
+    async (scenario) => {
+      // Set up the app to be installed
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+
+      // Add 2 players with the test app to the Scenario. The returned players
+      // can be destructured.
+      const [alice, bob] = await scenario.addPlayersWithApps([
+        appSource,
+        appSource,
+      ]);
+
+      // Shortcut peer discovery through gossip and register all agents in every
+      // conductor of the scenario.
+      await scenario.shareAllAgents();
+
+      // Alice creates a Mew
+      const actionHash: ActionHash = await createMew(alice.cells[0]);
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      // Bob likes Alice's Mew
+      await bob.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "add_hash_for_liker",
+        payload: {
+          base_liker: bob.agentPubKey,
+          target_hash: actionHash,
+        },
+      });
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      // Nothing has been read yet
+      let unreadCount: number = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "count_unread_notifications",
+        payload: null,
+      });
+      expect(unreadCount).toEqual(1);
+
+      const notifications: Notification[] = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_my_notifications",
+        payload: null,
+      });
+      assert.lengthOf(notifications, 1);
+
+      // Alice reads her notifications
+      const lastReadTimestamp: number = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "mark_notifications_read",
+        payload: notifications[0].timestamp,
+      });
+      expect(lastReadTimestamp).toEqual(notifications[0].timestamp);
+
+      unreadCount = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "count_unread_notifications",
+        payload: null,
+      });
+      expect(unreadCount).toEqual(0);
+
+      // The read cursor never moves back
+      const unchangedTimestamp: number = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "mark_notifications_read",
+        payload: notifications[0].timestamp - 1000,
+      });
+      expect(unchangedTimestamp).toEqual(notifications[0].timestamp);
+
+      // Bob pins Alice's Mew
+      await bob.cells[0].callZome({
+        zome_name: "agent_pins",
+        fn_name: "add_hash_for_pinner",
+        payload: {
+          base_pinner: bob.agentPubKey,
+          target_hash: actionHash,
+        },
+      });
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      unreadCount = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "count_unread_notifications",
+        payload: null,
+      });
+      expect(unreadCount).toEqual(1);
+
+      const unreadNotifications: Notification[] =
+        await alice.cells[0].callZome({
+          zome_name: "mews",
+          fn_name: "get_notifications_for_agent",
+          payload: {
+            agent: alice.agentPubKey,
+            page: null,
+            unread_only: true,
+          },
+        });
+      assert.lengthOf(unreadNotifications, 1);
+      expect(unreadNotifications[0]).toMatchObject({
+        agent: bob.agentPubKey,
+        notification_type: { [NotificationTypeName.MyMewPinned]: null },
+      });
+
+      // All notifications are still listed
+      const count: number = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "count_my_notifications",
+        payload: null,
+      });
+      expect(count).toEqual(2);
+
+      // Only Alice can see her unread notifications
+      await expect(
+        bob.cells[0].callZome({
+          zome_name: "mews",
+          fn_name: "get_notifications_for_agent",
+          payload: {
+            agent: alice.agentPubKey,
+            page: null,
+            unread_only: true,
+          },
+        })
+      ).rejects.toThrow();
+    },
+    true,
+    { timeout: 500000 }
+  );
+});
+
+test("licks of my mews are pushed to me as signals", async () => {
+  await runScenario(
+    async (scenario) => {
+      // Set up the app to be installed
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+
+      // Add 2 players with the test app to the Scenario. The returned players
+      // can be destructured.
+      const [alice, bob] = await scenario.addPlayersWithApps([
+        appSource,
+        appSource,
+      ]);
+
+      // Shortcut peer discovery through gossip and register all agents in every
+      // conductor of the scenario.
+      await scenario.shareAllAgents();
+
+      // Alice creates a Mew
+      const actionHash: ActionHash = await createMew(alice.cells[0]);
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      const signalReceived = new Promise<AppSignal>((resolve) =>
+        alice.appAgentWs.on("signal", resolve)
+      );
+
+      // Bob licks Alice's Mew
+      await bob.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "like",
+        payload: actionHash,
+      });
+
+      const signal = await signalReceived;
+      assert.equal(signal.zome_name, "mews");
+
+      const notification = signal.payload as Notification;
+      expect(notification).toMatchObject({
+        agent: bob.agentPubKey,
+        notification_type: { [NotificationTypeName.MyMewLicked]: null },
+      });
+      assert.deepEqual(notification.feed_mew?.action_hash, actionHash);
+
+      // Bob's viewer flags are not sent to Alice
+      assert.isNull(notification.feed_mew?.is_licked);
+    },
+    true,
+    { timeout: 500000 }
+  );
+});
+
+test("notifications muted by my preferences are left out", async () => {
+  await runScenario(
+    async (scenario) => {
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+      const [alice, bob, carol] = await scenario.addPlayersWithApps([
+        appSource,
+        appSource,
+        appSource,
+      ]);
+      await scenario.shareAllAgents();
+
+      // Alice creates a Mew
+      const actionHash: ActionHash = await createMew(alice.cells[0]);
+
+      await dhtSync([alice, bob, carol], alice.cells[0].cell_id[0]);
+
+      // Alice disables unlick notifications and mutes Carol
+      const preferences: NotificationPreferences = {
+        disabled_types: [{ [NotificationTypeName.MyMewUnlicked]: null }],
+        muted_agents: [carol.agentPubKey],
+        muted_yarns: [],
+      };
+      await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "set_my_notification_preferences",
+        payload: preferences,
+      });
+      const storedPreferences: NotificationPreferences =
+        await alice.cells[0].callZome({
+          zome_name: "mews",
+          fn_name: "get_my_notification_preferences",
+          payload: null,
+        });
+      expect(storedPreferences).toEqual(preferences);
+
+      // Bob likes and unlikes Alice's Mew, Carol likes it
+      await bob.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "add_hash_for_liker",
+        payload: { base_liker: bob.agentPubKey, target_hash: actionHash },
+      });
+      await bob.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "remove_hash_for_liker",
+        payload: { base_liker: bob.agentPubKey, target_hash: actionHash },
+      });
+      await carol.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "add_hash_for_liker",
+        payload: { base_liker: carol.agentPubKey, target_hash: actionHash },
+      });
+
+      await dhtSync([alice, bob, carol], alice.cells[0].cell_id[0]);
+
+      // Only Bob's lick is left
+      const notifications: Notification[] = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_my_notifications",
+        payload: null,
+      });
+      expect(notifications.length).toEqual(1);
+      expect(notifications[0]).toMatchObject({
+        agent: bob.agentPubKey,
+        notification_type: { [NotificationTypeName.MyMewLicked]: null },
+      });
+
+      const count = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "count_my_notifications",
+        payload: null,
+      });
+      expect(count).toEqual(1);
+    },
+    true,
+    { timeout: 500000 }
+  );
+});
+
+test("notifications of the same type on the same mew can be grouped", async () => {
+  await runScenario(
+    async (scenario) => {
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+      const [alice, bob, carol] = await scenario.addPlayersWithApps([
+        appSource,
+        appSource,
+        appSource,
+      ]);
+      await scenario.shareAllAgents();
+
+      // Alice creates a Mew
+      const actionHash: ActionHash = await createMew(alice.cells[0]);
+
+      await dhtSync([alice, bob, carol], alice.cells[0].cell_id[0]);
+
+      // Bob and Carol both like Alice's Mew
+      await bob.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "add_hash_for_liker",
+        payload: { base_liker: bob.agentPubKey, target_hash: actionHash },
+      });
+      await carol.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "add_hash_for_liker",
+        payload: { base_liker: carol.agentPubKey, target_hash: actionHash },
+      });
+
+      await dhtSync([alice, bob, carol], alice.cells[0].cell_id[0]);
+
+      // The flat list still has one notification per lick
+      const notifications: Notification[] = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_my_notifications",
+        payload: null,
+      });
+      expect(notifications.length).toEqual(2);
+
+      // The grouped list has a single group with both agents, latest first
+      const groupedNotifications: GroupedNotification[] =
+        await alice.cells[0].callZome({
+          zome_name: "mews",
+          fn_name: "get_my_grouped_notifications",
+          payload: { limit: 10 },
+        });
+      expect(groupedNotifications.length).toEqual(1);
+      expect(groupedNotifications[0]).toMatchObject({
+        notification_type: { [NotificationTypeName.MyMewLicked]: null },
+        agents: [carol.agentPubKey, bob.agentPubKey],
+        count: 2,
+        latest_timestamp: notifications[0].timestamp,
+      });
+      expect(groupedNotifications[0].feed_mew?.action_hash).toEqual(actionHash);
+
+      // Without a window each notification is its own group
+      const ungroupedNotifications: GroupedNotification[] =
+        await alice.cells[0].callZome({
+          zome_name: "mews",
+          fn_name: "get_grouped_notifications_for_agent",
+          payload: { agent: alice.agentPubKey, group_window_seconds: 0 },
+        });
+      expect(ungroupedNotifications.length).toEqual(2);
+    },
+    true,
+    { timeout: 500000 }
+  );
+});
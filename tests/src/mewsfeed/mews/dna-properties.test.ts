@@ -1,7 +1,11 @@
 import { ActionHash } from "@holochain/client";
 import { runScenario } from "@holochain/tryorama";
 import { assert, expect, test } from "vitest";
-import { Mew, MewTypeName } from "../../../../ui/src/types/types.js";
+import {
+  LinkTargetName,
+  Mew,
+  MewTypeName,
+} from "../../../../ui/src/types/types.js";
 import {
   mewsfeedAppBundleSource,
   mewsfeedAppBundleSourceNoLengthLimits,
@@ -57,6 +61,66 @@ test("Mew must not be longer than DNA property mew_characters_max chars", async
   );
 });
 
+test("Mew length is counted in grapheme clusters", async () => {
+  await runScenario(
+    async (scenario) => {
+      // Set up the app to be installed
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+
+      // Add 2 players with the test app to the Scenario. The returned players
+      // can be destructured.
+      const [alice] = await scenario.addPlayersWithApps([appSource]);
+
+      // Shortcut peer discovery through gossip and register all agents in every
+      // conductor of the scenario.
+      await scenario.shareAllAgents();
+
+      // 200 multi-byte characters, including a multi-codepoint emoji
+      const createMewInput: Mew = {
+        text: new Array(199).fill("猫").join("") + "👩‍👩‍👧",
+        links: [],
+        mew_type: { [MewTypeName.Original]: null },
+      };
+      const action_hash: ActionHash = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "create_mew",
+        payload: createMewInput,
+      });
+      assert.ok(action_hash, "alice created a mew of 200 graphemes");
+
+      createMewInput.text = new Array(201).fill("猫").join("");
+      await expect(
+        alice.cells[0].callZome({
+          zome_name: "mews",
+          fn_name: "create_mew",
+          payload: createMewInput,
+        })
+      ).rejects.toThrow();
+
+      // A url link counts as a fixed number of characters, however long its label
+      const createMewWithLinkInput: Mew = {
+        text:
+          new Array(176).fill("a").join("") +
+          " ^" +
+          new Array(50).fill("b").join(""),
+        links: [{ [LinkTargetName.Url]: "https://example.com" }],
+        mew_type: { [MewTypeName.Original]: null },
+      };
+      const link_action_hash: ActionHash = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "create_mew",
+        payload: createMewWithLinkInput,
+      });
+      assert.ok(
+        link_action_hash,
+        "alice created a mew with a long url link label"
+      );
+    },
+    true,
+    { timeout: 500000 }
+  );
+});
+
 test("Mew must not be shorter than DNA property mew_characters_min chars", async () => {
   await runScenario(
     async (scenario) => {
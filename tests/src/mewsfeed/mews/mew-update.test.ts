@@ -0,0 +1,103 @@
+import { ActionHash, Record } from "@holochain/client";
+import { dhtSync, runScenario } from "@holochain/tryorama";
+import { assert, expect, test } from "vitest";
+import { FeedMew } from "../../../../ui/src/types/types";
+import { mewsfeedAppBundleSource } from "../../common";
+import { createMew } from "./common";
+
+test("Mew can be edited by its author and keeps its revision history", async () => {
+  await runScenario(
+    async (scenario) => {
+      // Set up the app to be installed
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+
+      // Add 2 players with the test app to the Scenario. The returned players
+      // can be destructured.
+      const [alice, bob] = await scenario.addPlayersWithApps([
+        appSource,
+        appSource,
+      ]);
+
+      // Shortcut peer discovery through gossip and register all agents in every
+      // conductor of the scenario.
+      await scenario.shareAllAgents();
+
+      // Alice creates a Mew
+      const actionHash: ActionHash = await createMew(alice.cells[0]);
+      assert.ok(actionHash);
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      // Bob cannot edit alice's mew
+      await expect(
+        bob.cells[0].callZome({
+          zome_name: "mews",
+          fn_name: "update_mew",
+          payload: {
+            original_mew_hash: actionHash,
+            updated_mew: {
+              text: "bob's version of the mew",
+              links: [],
+              mew_type: { Original: null },
+            },
+          },
+        })
+      ).rejects.toThrow();
+
+      // Alice cannot change the mew type
+      await expect(
+        alice.cells[0].callZome({
+          zome_name: "mews",
+          fn_name: "update_mew",
+          payload: {
+            original_mew_hash: actionHash,
+            updated_mew: {
+              text: "now it is a reply",
+              links: [],
+              mew_type: { Reply: actionHash },
+            },
+          },
+        })
+      ).rejects.toThrow();
+
+      // Alice edits her mew
+      const feedMew: FeedMew = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "update_mew_with_context",
+        payload: {
+          original_mew_hash: actionHash,
+          updated_mew: {
+            text: "edited mew text",
+            links: [],
+            mew_type: { Original: null },
+          },
+        },
+      });
+      expect(feedMew.mew.text).toEqual("edited mew text");
+      expect(feedMew.action_hash).toEqual(actionHash);
+      assert.ok(feedMew.edited_timestamp);
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      // Bob sees the latest revision
+      const bobFeedMew: FeedMew = await bob.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_mew_with_context",
+        payload: actionHash,
+      });
+      expect(bobFeedMew.mew.text).toEqual("edited mew text");
+      assert.ok(bobFeedMew.edited_timestamp);
+
+      // Bob gets the full revision history, oldest first
+      const revisions: Record[] = await bob.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_mew_revisions",
+        payload: actionHash,
+      });
+      expect(revisions.length).toEqual(2);
+      expect(revisions[0].signed_action.hashed.hash).toEqual(actionHash);
+    },
+    true,
+    { timeout: 100000 }
+  );
+});
@@ -1,7 +1,12 @@
 import { ActionHash } from "@holochain/client";
 import { dhtSync, pause, runScenario } from "@holochain/tryorama";
 import { assert, expect, test } from "vitest";
-import { FeedMew, Mew, MewTypeName } from "../../../../ui/src/types/types";
+import {
+  FeedMew,
+  FeedMewOptions,
+  Mew,
+  MewTypeName,
+} from "../../../../ui/src/types/types";
 import { mewsfeedAppBundleSource } from "../../common";
 import { createMew } from "./common";
 
@@ -345,3 +350,152 @@ test("Mew with context contains is_pinned", async () => {
     { timeout: 100000 }
   );
 });
+
+test("Batch of mews with context shares embedded mews and keeps order", async () => {
+  await runScenario(
+    async (scenario) => {
+      // Set up the app to be installed
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+
+      // Add 2 players with the test app to the Scenario. The returned players
+      // can be destructured.
+      const [alice, bob] = await scenario.addPlayersWithApps([
+        appSource,
+        appSource,
+      ]);
+
+      // Shortcut peer discovery through gossip and register all agents in every
+      // conductor of the scenario.
+      await scenario.shareAllAgents();
+
+      // Alice creates a Mew
+      const actionHash: ActionHash = await createMew(alice.cells[0]);
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      // Bob quotes it twice and licks it
+      const quoteHash1: ActionHash = await createMew(bob.cells[0], {
+        text: "first quote of alice",
+        links: [],
+        mew_type: { [MewTypeName.Quote]: actionHash },
+      });
+      const quoteHash2: ActionHash = await createMew(bob.cells[0], {
+        text: "second quote of alice",
+        links: [],
+        mew_type: { [MewTypeName.Quote]: actionHash },
+      });
+      await bob.cells[0].callZome({
+        zome_name: "likes",
+        fn_name: "like",
+        payload: actionHash,
+      });
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      const feedMews: FeedMew[] = await bob.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_batch_mews_with_context",
+        payload: [quoteHash2, actionHash, quoteHash1],
+      });
+      assert.lengthOf(feedMews, 3);
+      assert.deepEqual(feedMews[0].action_hash, quoteHash2);
+      assert.deepEqual(feedMews[1].action_hash, actionHash);
+      assert.deepEqual(feedMews[2].action_hash, quoteHash1);
+
+      expect(feedMews[1].quotes_count).toEqual(2);
+      expect(feedMews[1].is_quoted).true;
+      expect(feedMews[1].is_licked).true;
+      expect(feedMews[1].licks_count).toEqual(1);
+      assert.deepEqual(feedMews[0].original_mew?.action_hash, actionHash);
+      assert.deepEqual(feedMews[0].original_mew, feedMews[2].original_mew);
+    },
+    true,
+    { timeout: 100000 }
+  );
+});
+
+test("Mew with context only includes the requested context", async () => {
+  await runScenario(
+    async (scenario) => {
+      // Set up the app to be installed
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+
+      // Add 2 players with the test app to the Scenario. The returned players
+      // can be destructured.
+      const [alice, bob] = await scenario.addPlayersWithApps([
+        appSource,
+        appSource,
+      ]);
+
+      // Shortcut peer discovery through gossip and register all agents in every
+      // conductor of the scenario.
+      await scenario.shareAllAgents();
+
+      // Alice creates a Mew and Bob quotes it
+      const actionHash: ActionHash = await createMew(alice.cells[0]);
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      const quoteHash: ActionHash = await createMew(bob.cells[0], {
+        text: "quote of alice",
+        links: [],
+        mew_type: { [MewTypeName.Quote]: actionHash },
+      });
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      // Bob gets the quote without counts, viewer flags or embedded mew
+      const options: FeedMewOptions = {
+        include_counts: false,
+        include_viewer_flags: false,
+        include_author_profile: true,
+        include_embed: false,
+      };
+      const feedMew: FeedMew = await bob.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_mew_with_context",
+        payload: { mew_hash: quoteHash, options },
+      });
+      assert.deepEqual(feedMew.action_hash, quoteHash);
+      assert.isNull(feedMew.replies_count);
+      assert.isNull(feedMew.quotes_count);
+      assert.isNull(feedMew.licks_count);
+      assert.isNull(feedMew.mewmews_count);
+      assert.isNull(feedMew.is_pinned);
+      assert.isNull(feedMew.is_licked);
+      assert.isNull(feedMew.is_quoted);
+      assert.isNull(feedMew.original_mew);
+
+      // Alice gets both mews with counts only
+      const feedMews: FeedMew[] = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_batch_mews_with_context",
+        payload: {
+          mew_hashes: [actionHash, quoteHash],
+          options: {
+            ...options,
+            include_counts: true,
+            include_author_profile: false,
+          },
+        },
+      });
+      assert.lengthOf(feedMews, 2);
+      expect(feedMews[0].quotes_count).toEqual(1);
+      assert.isNull(feedMews[0].is_quoted);
+      assert.isNull(feedMews[1].author_profile);
+      assert.isNull(feedMews[1].original_mew);
+
+      // A bare hash still gets the full context
+      const fullFeedMew: FeedMew = await bob.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_mew_with_context",
+        payload: quoteHash,
+      });
+      expect(fullFeedMew.quotes_count).toEqual(0);
+      expect(fullFeedMew.is_quoted).false;
+      assert.deepEqual(fullFeedMew.original_mew?.action_hash, actionHash);
+    },
+    true,
+    { timeout: 100000 }
+  );
+});
@@ -1,5 +1,5 @@
 import { ActionHash } from "@holochain/client";
-import { pause, runScenario } from "@holochain/tryorama";
+import { dhtSync, pause, runScenario } from "@holochain/tryorama";
 import { assert, expect, test } from "vitest";
 import {
   FeedMew,
@@ -596,3 +596,139 @@ test("Cashtags list are time-paginated", async () => {
     { timeout: 500000 }
   );
 });
+
+test("Cashtags are only indexed for tickers and removed when the mew is deleted", async () => {
+  await runScenario(
+    async (scenario) => {
+      // Set up the app to be installed
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+
+      // Add 2 players with the test app to the Scenario. The returned players
+      // can be destructured.
+      const [alice] = await scenario.addPlayersWithApps([appSource]);
+
+      // Shortcut peer discovery through gossip and register all agents in every
+      // conductor of the scenario.
+      await scenario.shareAllAgents();
+
+      const createMewInput: Mew = {
+        text: "Bought $HOLO for $5 today, $HOLO to the moon",
+        links: [],
+        mew_type: { [MewTypeName.Original]: null },
+      };
+      const mewActionHash: ActionHash = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "create_mew",
+        payload: createMewInput,
+      });
+
+      const cashtaggedMews: FeedMew[] = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_mews_for_cashtag_with_context",
+        payload: {
+          cashtag: "$HOLO",
+        },
+      });
+      assert.lengthOf(cashtaggedMews, 1, "mew is indexed once per cashtag");
+      assert.deepEqual(cashtaggedMews[0].action_hash, mewActionHash);
+
+      const amountMews: FeedMew[] = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_mews_for_cashtag_with_context",
+        payload: {
+          cashtag: "$5",
+        },
+      });
+      assert.lengthOf(amountMews, 0, "amounts are not cashtags");
+
+      await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "delete_mew",
+        payload: mewActionHash,
+      });
+
+      const cashtaggedMewsAfterDelete: FeedMew[] =
+        await alice.cells[0].callZome({
+          zome_name: "mews",
+          fn_name: "get_mews_for_cashtag_with_context",
+          payload: {
+            cashtag: "$HOLO",
+          },
+        });
+      assert.lengthOf(cashtaggedMewsAfterDelete, 0);
+    },
+    true,
+    { timeout: 500000 }
+  );
+});
+
+test("Tag and mention links must match the mew content and author", async () => {
+  await runScenario(
+    async (scenario) => {
+      // Set up the app to be installed
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+
+      // Add 2 players with the test app to the Scenario. The returned players
+      // can be destructured.
+      const [alice, bob] = await scenario.addPlayersWithApps([
+        appSource,
+        appSource,
+      ]);
+
+      // Shortcut peer discovery through gossip and register all agents in every
+      // conductor of the scenario.
+      await scenario.shareAllAgents();
+
+      const createMewInput: Mew = {
+        text: "My Mew with #hashtag and @mention",
+        links: [{ [LinkTargetName.Mention]: bob.agentPubKey }],
+        mew_type: { [MewTypeName.Original]: null },
+      };
+      const mewActionHash: ActionHash = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "create_mew",
+        payload: createMewInput,
+      });
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      // Alice cannot attach a hashtag that is not in her mew
+      await expect(
+        alice.cells[0].callZome({
+          zome_name: "mews",
+          fn_name: "add_hashtag_for_mew",
+          payload: {
+            base_hashtag: "#spam",
+            target_mew_hash: mewActionHash,
+          },
+        })
+      ).rejects.toThrow();
+
+      // Alice cannot mention an agent that is not in her mew's links
+      await expect(
+        alice.cells[0].callZome({
+          zome_name: "mews",
+          fn_name: "add_mention_for_mew",
+          payload: {
+            base_mention: alice.agentPubKey,
+            target_mew_hash: mewActionHash,
+          },
+        })
+      ).rejects.toThrow();
+
+      // Bob cannot tag alice's mew, even with one of its hashtags
+      await expect(
+        bob.cells[0].callZome({
+          zome_name: "mews",
+          fn_name: "add_hashtag_for_mew",
+          payload: {
+            base_hashtag: "#hashtag",
+            target_mew_hash: mewActionHash,
+          },
+        })
+      ).rejects.toThrow();
+    },
+    true,
+    { timeout: 500000 }
+  );
+});
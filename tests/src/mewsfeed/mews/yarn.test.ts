@@ -0,0 +1,153 @@
+import { ActionHash } from "@holochain/client";
+import { dhtSync, runScenario } from "@holochain/tryorama";
+import { assert, expect, test } from "vitest";
+import {
+  Mew,
+  MewTypeName,
+  Page,
+  YarnNode,
+} from "../../../../ui/src/types/types.js";
+import { mewsfeedAppBundleSource } from "../../common.js";
+
+test("Yarn is returned as a reply tree from its root, with deleted placeholders", async () => {
+  await runScenario(
+    async (scenario) => {
+      // Set up the app to be installed
+      const appSource = { appBundleSource: mewsfeedAppBundleSource };
+
+      // Add 2 players with the test app to the Scenario. The returned players
+      // can be destructured.
+      const [alice, bob] = await scenario.addPlayersWithApps([
+        appSource,
+        appSource,
+      ]);
+
+      // Shortcut peer discovery through gossip and register all agents in every
+      // conductor of the scenario.
+      await scenario.shareAllAgents();
+
+      const rootMewInput: Mew = {
+        text: "alice-root-mew",
+        links: [],
+        mew_type: { [MewTypeName.Original]: null },
+      };
+      const rootHash: ActionHash = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "create_mew",
+        payload: rootMewInput,
+      });
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      const bobReplyHash: ActionHash = await bob.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "create_mew",
+        payload: {
+          text: "bob-reply-to-root",
+          links: [],
+          mew_type: { [MewTypeName.Reply]: rootHash },
+        },
+      });
+      const secondBobReplyHash: ActionHash = await bob.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "create_mew",
+        payload: {
+          text: "bob-second-reply-to-root",
+          links: [],
+          mew_type: { [MewTypeName.Reply]: rootHash },
+        },
+      });
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      const aliceNestedReplyHash: ActionHash = await alice.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "create_mew",
+        payload: {
+          text: "alice-reply-to-bob",
+          links: [],
+          mew_type: { [MewTypeName.Reply]: bobReplyHash },
+        },
+      });
+
+      // Bob deletes his first reply, which still has a reply below it
+      await bob.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "delete_mew",
+        payload: bobReplyHash,
+      });
+
+      await dhtSync([alice, bob], alice.cells[0].cell_id[0]);
+
+      // Getting the yarn from the deepest reply starts at the root
+      const yarn: YarnNode = await bob.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_yarn",
+        payload: {
+          mew_hash: aliceNestedReplyHash,
+          max_depth: 2,
+          replies_page: { limit: 10, direction: { Ascending: null } },
+        },
+      });
+      assert.deepEqual(yarn.action_hash, rootHash);
+      expect(yarn.feed_mew?.mew.text).toEqual("alice-root-mew");
+      assert.lengthOf(yarn.replies.items, 2);
+      assert.isFalse(yarn.replies.has_more);
+
+      const deletedReply = yarn.replies.items[0];
+      assert.deepEqual(deletedReply.action_hash, bobReplyHash);
+      assert.isNull(deletedReply.feed_mew);
+      assert.ok(deletedReply.deleted_timestamp);
+      assert.lengthOf(deletedReply.replies.items, 1);
+      assert.deepEqual(
+        deletedReply.replies.items[0].action_hash,
+        aliceNestedReplyHash
+      );
+      assert.deepEqual(yarn.replies.items[1].action_hash, secondBobReplyHash);
+
+      // Depth limit stops the tree while flagging that more replies exist
+      const shallowYarn: YarnNode = await bob.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_yarn",
+        payload: {
+          mew_hash: rootHash,
+          max_depth: 0,
+        },
+      });
+      assert.lengthOf(shallowYarn.replies.items, 0);
+      assert.isTrue(shallowYarn.replies.has_more);
+
+      // Replies of a node are paginated
+      const firstPage: Page<YarnNode> = await bob.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_yarn_replies",
+        payload: {
+          mew_hash: rootHash,
+          max_depth: 1,
+          page: { limit: 1, direction: { Ascending: null } },
+        },
+      });
+      assert.lengthOf(firstPage.items, 1);
+      assert.isTrue(firstPage.has_more);
+
+      const secondPage: Page<YarnNode> = await bob.cells[0].callZome({
+        zome_name: "mews",
+        fn_name: "get_yarn_replies",
+        payload: {
+          mew_hash: rootHash,
+          max_depth: 1,
+          page: {
+            after_cursor: firstPage.next_cursor,
+            limit: 1,
+            direction: { Ascending: null },
+          },
+        },
+      });
+      assert.lengthOf(secondPage.items, 1);
+      assert.deepEqual(secondPage.items[0].action_hash, secondBobReplyHash);
+      assert.isFalse(secondPage.has_more);
+    },
+    true,
+    { timeout: 500000 }
+  );
+});
@@ -1,81 +1,42 @@
-import {
-  AppAgentClient,
-  decodeHashFromBase64,
-  encodeHashToBase64,
-} from "@holochain/client";
-import { StateTree, defineStore } from "pinia";
-import { computed, ref } from "vue";
+import { AppAgentClient } from "@holochain/client";
+import { defineStore } from "pinia";
+import { ref } from "vue";
 import { Notification } from "@/types/types";
-import { encode, decode } from "@msgpack/msgpack";
-
-const notificationToKey = (notification: Notification) => {
-  const keyObj = {
-    notificationType: notification.notification_type,
-    timestamp: notification.timestamp,
-    agentPubKeyB64: notification.agent,
-    mewActionHash: notification.feed_mew
-      ? notification.feed_mew.action_hash
-      : null,
-  };
-
-  return encodeHashToBase64(encode(keyObj));
-};
 
 export const makeUseNotificationsReadStore = (client: AppAgentClient) =>
-  defineStore(
-    "notificationsRead",
-    () => {
-      const notificationsCount = ref<number>(0);
-      const notificationsRead = ref<{
-        [key: string]: boolean;
-      }>({});
-      const unreadCount = computed(
-        () =>
-          notificationsCount.value -
-          Object.values(notificationsRead.value).filter((r) => r).length
-      );
-
-      function markRead(notification: Notification) {
-        const key = notificationToKey(notification);
-        notificationsRead.value[key] = true;
-      }
-
-      function addNotificationStatus(
-        notification: Notification,
-        status = false
-      ) {
-        const key = notificationToKey(notification);
-
-        if (notificationsRead.value[key] === undefined) {
-          notificationsRead.value[key] = status;
-        }
-      }
-
-      function setNotificationsCount(count: number) {
-        if (count > notificationsCount.value) {
-          notificationsCount.value = count;
-        }
-      }
+  defineStore("notificationsRead", () => {
+    const unreadCount = ref<number>(0);
+    const lastReadTimestamp = ref<number>(0);
+
+    async function fetchUnreadCount() {
+      const count: number = await client.callZome({
+        role_name: "mewsfeed",
+        zome_name: "mews",
+        fn_name: "count_unread_notifications",
+        payload: null,
+      });
+      unreadCount.value = count;
+
+      return count;
+    }
 
-      return {
-        notificationsRead,
-        unreadCount,
-        markRead,
-        addNotificationStatus,
-        setNotificationsCount,
-      };
-    },
-    {
-      persist: {
-        key: `notificationsRead/${encodeHashToBase64(client.myPubKey)}`,
-        serializer: {
-          serialize(value: StateTree): string {
-            return encodeHashToBase64(encode(value));
-          },
-          deserialize(value: string): StateTree {
-            return decode(decodeHashFromBase64(value)) as StateTree;
-          },
-        },
-      },
+    // Notifications are read newest first, so the read cursor moves to the newest one seen
+    async function markRead(notification: Notification) {
+      if (notification.timestamp <= lastReadTimestamp.value) return;
+      lastReadTimestamp.value = notification.timestamp;
+
+      lastReadTimestamp.value = await client.callZome({
+        role_name: "mewsfeed",
+        zome_name: "mews",
+        fn_name: "mark_notifications_read",
+        payload: notification.timestamp,
+      });
+      await fetchUnreadCount();
     }
-  );
+
+    return {
+      unreadCount,
+      fetchUnreadCount,
+      markRead,
+    };
+  });
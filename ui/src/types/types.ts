@@ -75,22 +75,66 @@ export type MewType =
       [MewTypeName.Quote]: ActionHash;
     };
 
+export enum FlowPromptKindName {
+  Kind = "Kind",
+  Mention = "Mention",
+  Cashtag = "Cashtag",
+  Between = "Between",
+}
+
+export type FlowPromptKind =
+  | {
+      [FlowPromptKindName.Kind]: null;
+    }
+  | {
+      [FlowPromptKindName.Mention]: AgentPubKey;
+    }
+  | {
+      [FlowPromptKindName.Cashtag]: null;
+    }
+  | {
+      [FlowPromptKindName.Between]: null;
+    };
+
+export interface FlowPrompt {
+  insert_text: string;
+  display: string;
+  kind: FlowPromptKind;
+}
+
 export interface FeedMew {
   mew: Mew;
   action: Create;
   action_hash: ActionHash;
-  replies_count: number;
-  quotes_count: number;
-  licks_count: number;
-  mewmews_count: number;
-  is_pinned: boolean;
-  is_licked: boolean;
-  is_mewmewed: boolean;
-  is_replied: boolean;
-  is_quoted: boolean;
+  replies_count: number | null;
+  quotes_count: number | null;
+  licks_count: number | null;
+  mewmews_count: number | null;
+  is_pinned: boolean | null;
+  is_licked: boolean | null;
+  is_mewmewed: boolean | null;
+  is_replied: boolean | null;
+  is_quoted: boolean | null;
   author_profile: Profile | null;
   deleted_timestamp: number | null;
+  edited_timestamp: number | null;
   original_mew: EmbedMew | null;
+  reactions: Record<string, number> | null;
+  my_reactions: string[] | null;
+}
+
+export interface FeedMewOptions {
+  include_counts: boolean;
+  include_viewer_flags: boolean;
+  include_author_profile: boolean;
+  include_embed: boolean;
+}
+
+export interface YarnNode {
+  action_hash: ActionHash;
+  feed_mew: FeedMew | null;
+  deleted_timestamp: number | null;
+  replies: Page<YarnNode>;
 }
 
 export interface EmbedMew {
@@ -142,9 +186,18 @@ export interface SigningCredentialsJson
   signingKey: number[];
 }
 
+export enum MewLengthCounting {
+  Bytes = "Bytes",
+  Graphemes = "Graphemes",
+}
+
 export interface MewsfeedDnaProperties {
   mew_characters_min: number | null;
   mew_characters_max: number | null;
+  mew_edit_window_seconds: number | null;
+  mew_length_counting: MewLengthCounting | null;
+  allowed_reactions: string[] | null;
+  max_pins: number | null;
 }
 
 export type SearchResultOption = {
@@ -174,6 +227,15 @@ export type Notification = {
   feed_mew: FeedMew | null;
 };
 
+export type GroupedNotification = {
+  notification_type: NotificationType;
+  agents: AgentPubKey[];
+  latest_agent_profile: Profile | null;
+  count: number;
+  latest_timestamp: number;
+  feed_mew: FeedMew | null;
+};
+
 export enum NotificationTypeName {
   MyMewLicked = "MyMewLicked",
   MyMewUnlicked = "MyMewUnlicked",
@@ -184,6 +246,8 @@ export enum NotificationTypeName {
   MyAgentFollowed = "MyAgentFollowed",
   MyAgentUnfollowed = "MyAgentUnfollowed",
   FollowedYarnResponded = "FollowedYarnResponded",
+  MyAgentFollowRequested = "MyAgentFollowRequested",
+  MyFollowRequestAccepted = "MyFollowRequestAccepted",
 }
 
 export type NotificationType =
@@ -195,7 +259,15 @@ export type NotificationType =
   | { [NotificationTypeName.MyAgentMentioned]: null }
   | { [NotificationTypeName.MyAgentFollowed]: null }
   | { [NotificationTypeName.MyAgentUnfollowed]: null }
-  | { [NotificationTypeName.FollowedYarnResponded]: null };
+  | { [NotificationTypeName.FollowedYarnResponded]: null }
+  | { [NotificationTypeName.MyAgentFollowRequested]: null }
+  | { [NotificationTypeName.MyFollowRequestAccepted]: null };
+
+export interface NotificationPreferences {
+  disabled_types: NotificationType[];
+  muted_agents: AgentPubKey[];
+  muted_yarns: ActionHash[];
+}
 
 export declare type CacheData<R = any, P = any> = {
   data: R;
@@ -215,6 +287,23 @@ export type TimestampPagination = {
   limit: number;
 };
 
+export type Cursor = {
+  timestamp: number;
+  hash: Uint8Array;
+};
+
+export type CursorPagination = {
+  after_cursor?: Cursor | null;
+  direction?: PaginationDirectionType | null;
+  limit: number;
+};
+
+export type Page<T> = {
+  items: T[];
+  next_cursor: Cursor | null;
+  has_more: boolean;
+};
+
 export type PaginationDirectionType =
   | { [PaginationDirectionName.Ascending]: null }
   | { [PaginationDirectionName.Descending]: null };
@@ -3,10 +3,17 @@ import {
   LinkTargetName,
   MentionLinkTarget,
   MewContentPart,
+  MewLengthCounting,
   MewTagType,
   UrlLinkTarget,
 } from "@/types/types";
-import { isRawUrl, isTag, TAG_OR_RAW_URL_REGEX, TAG_SYMBOLS } from "./tags";
+import {
+  isRawUrl,
+  isTag,
+  LINK_TAG_REGEX_GLOBAL,
+  TAG_OR_RAW_URL_REGEX,
+  TAG_SYMBOLS,
+} from "./tags";
 import { ROUTES } from "@/router";
 import { AgentPubKey, encodeHashToBase64 } from "@holochain/client";
 import { RouteLocationRaw } from "vue-router";
@@ -89,3 +96,36 @@ export const splitMewTextIntoContentParts = (
 
   return contentParts;
 };
+
+// Keep in sync with MEW_URL_LINK_CHARACTERS in the mews integrity zome
+export const MEW_URL_LINK_CHARACTERS = 23;
+
+const countGraphemes = (text: string): number =>
+  Array.from(new Intl.Segmenter().segment(text)).length;
+
+export const getMewTextLength = (
+  text: string,
+  links: LinkTarget[],
+  counting: MewLengthCounting | null
+): number => {
+  if (counting === MewLengthCounting.Bytes) {
+    return new TextEncoder().encode(text).length;
+  }
+
+  const urlLinksCount = links.filter(
+    (link) => LinkTargetName.Url in link
+  ).length;
+  const urlLinkTags = (text.match(LINK_TAG_REGEX_GLOBAL) || []).slice(
+    0,
+    urlLinksCount
+  );
+  const urlLinkTagsLength = urlLinkTags
+    .map(countGraphemes)
+    .reduce((total, length) => total + length, 0);
+
+  return (
+    countGraphemes(text) -
+    urlLinkTagsLength +
+    urlLinkTags.length * MEW_URL_LINK_CHARACTERS
+  );
+};
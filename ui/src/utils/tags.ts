@@ -14,8 +14,9 @@ const FLOW_TAG_REGEX = new RegExp(FLOW_TAG_REGEX_STRING, "mi");
 
 const LINK_TAG_REGEX_STRING = `\\B\\${TAG_SYMBOLS.LINK}\\w+`;
 const LINK_TAG_REGEX = new RegExp(LINK_TAG_REGEX_STRING, "mi");
+export const LINK_TAG_REGEX_GLOBAL = new RegExp(LINK_TAG_REGEX_STRING, "gmi");
 
-const CASH_TAG_REGEX_STRING = `\\B\\${TAG_SYMBOLS.CASHTAG}\\w+`;
+const CASH_TAG_REGEX_STRING = `\\B\\${TAG_SYMBOLS.CASHTAG}[^\\W\\d_]\\w*`;
 const CASH_TAG_REGEX = new RegExp(CASH_TAG_REGEX_STRING, "mi");
 
 const HASH_TAG_REGEX_STRING = `\\B\\${TAG_SYMBOLS.HASHTAG}\\w+`;